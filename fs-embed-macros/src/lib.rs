@@ -1,13 +1,25 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{Lit, LitStr, parse::Parse, parse_macro_input};
-
-
+use std::path::Path;
+use syn::{Ident, Lit, LitBool, LitStr, Token, parse::Parse, parse_macro_input};
 
 /// Embed a directory at compile time, returning a `Dir` enum. The path should be a literal string
 /// and strictly relative to the crate root.
 /// fs_embed!("dir")                 → Dir::from_embedded
+/// fs_embed!("dir", mount = "a/b")  → embedded paths are prefixed with `a/b/`
+/// fs_embed!("dir", allow_non_utf8 = true) → skip the check that every embedded path is UTF-8
+/// fs_embed!("dir", normalize_newlines = true) → embed text files with `\r\n` rewritten to `\n`
+/// fs_embed!("dir", allow_empty = true)     → accept a directory without any files
+/// fs_embed!("dir", build_id = true)        → record `FS_EMBED_BUILD_ID` or the build time, see `Dir::build_id`
+/// fs_embed!("dir", metadata = false)       → embed no timestamps; `File::metadata` then fails and
+///                                            other APIs use the Unix epoch as the mtime
+///
+/// Entries are always emitted in sorted path order, independent of the order the filesystem
+/// lists them in. Each file's modification time is the only timestamp recorded (access and
+/// creation times are not), so a rebuild of the same checkout expands to the same tokens;
+/// with `metadata = false`, a fresh checkout with new mtimes does too (unless `build_id` falls
+/// back to the build time).
 #[proc_macro]
 pub fn fs_embed(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as EmbedArgs);
@@ -16,51 +28,348 @@ pub fn fs_embed(input: TokenStream) -> TokenStream {
         Lit::Str(s) => s,
         other => return compile_error("first argument must be a string literal", other.span()),
     };
-
-    let rel_path = rel_lit.value();
     let call_span = rel_lit.span(); // proc_macro2::Span
 
-    // ── validate directory exists inside crate root ────────────────────────
-    let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
-        Ok(dir) => dir,
-        Err(_) => return compile_error("fs_embed!: CARGO_MANIFEST_DIR not set", call_span),
+    let full_path = match resolve_in_crate("fs_embed!", &rel_lit) {
+        Ok(p) => p,
+        Err(err) => return err,
+    };
+
+    let mount: Vec<String> = match &args.mount {
+        Some(mount) => match mount_segments(&mount.value()) {
+            Some(segments) => segments,
+            None => {
+                return compile_error(
+                    "fs_embed!: mount must be a relative path without `.` or `..` segments",
+                    mount.span(),
+                );
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let root = Path::new(&full_path);
+    if !args.allow_non_utf8 {
+        let invalid = non_utf8_paths(root);
+        if !invalid.is_empty() {
+            let msg = format!(
+                "fs_embed!: embedded paths must be valid UTF-8 (use `allow_non_utf8 = true` to opt out):\n  {}",
+                invalid.join("\n  ")
+            );
+            return compile_error(msg, call_span);
+        }
+    }
+    if !args.allow_empty && !has_files(root) {
+        let msg = format!(
+            "fs_embed!: no files found under `{}`; check the path and .gitignore (use `allow_empty = true` for an intentionally empty directory)",
+            rel_lit.value()
+        );
+        return compile_error(msg, call_span);
+    }
+    let tree = match expand_dir(root, root, &mount, args.normalize_newlines, args.metadata) {
+        Ok(tree) => tree,
+        Err(msg) => return compile_error(msg, call_span),
+    };
+    let tree = wrap_mount(tree, &mount);
+
+    let full_literal: LitStr = LitStr::new(&full_path, call_span);
+    let source_literal = source_path(&rel_lit.value());
+    let mount_literal = mount.join("/");
+
+    let build_id = if args.build_id {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+            .to_string();
+        // `option_env!` in the expansion makes cargo rebuild when the variable changes.
+        quote! {
+            Some(match option_env!("FS_EMBED_BUILD_ID") {
+                Some(id) => id,
+                None => #timestamp,
+            })
+        }
+    } else {
+        quote!(None)
     };
 
-    let full_path = match std::path::Path::new(&manifest_dir)
+    // The mount is recorded too, so `into_dynamic` serves the same paths from disk.
+    quote! {
+        ::fs_embed::Dir::from_embedded_parts(
+            #tree,
+            #full_literal,
+            Some(#source_literal),
+            #mount_literal,
+            #build_id,
+        )
+    }
+    .into()
+}
+
+/// Embed a single file at compile time, returning its contents as a `&'static [u8]`.
+/// The path should be a literal string and strictly relative to the crate root.
+/// fs_embed_file!("file")           → include_bytes!("<crate root>/file")
+#[proc_macro]
+pub fn fs_embed_file(input: TokenStream) -> TokenStream {
+    let rel_lit = parse_macro_input!(input as LitStr);
+    let call_span = rel_lit.span();
+
+    let full_path = match resolve_in_crate("fs_embed_file!", &rel_lit) {
+        Ok(p) => p,
+        Err(err) => return err,
+    };
+
+    if !Path::new(&full_path).is_file() {
+        let msg = format!("fs_embed_file!: not a file: {}", rel_lit.value());
+        return compile_error(&msg, call_span);
+    }
+
+    let full_literal: LitStr = LitStr::new(&full_path, call_span);
+    quote! { include_bytes!(#full_literal) as &'static [u8] }.into()
+}
+
+/// Resolves a path literal against `CARGO_MANIFEST_DIR`, requiring it to exist,
+/// be valid UTF-8 and stay inside the crate root.
+fn resolve_in_crate(macro_name: &str, rel_lit: &LitStr) -> Result<String, TokenStream> {
+    let rel_path = rel_lit.value();
+    let call_span = rel_lit.span();
+
+    // ── validate path exists inside crate root ─────────────────────────────
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        compile_error(
+            format!("{macro_name}: CARGO_MANIFEST_DIR not set"),
+            call_span,
+        )
+    })?;
+
+    let full_path = std::path::Path::new(&manifest_dir)
         .join(&rel_path)
         .canonicalize()
         .map_err(|_| {
-            syn::Error::new(
+            compile_error(
+                format!("{macro_name}: failed to resolve path: {}", rel_path),
                 call_span,
-                format!("fs_embed!: failed to resolve path: {}", rel_path),
             )
-        }) {
-        Ok(p) => p,
-        Err(msg) => return compile_error(msg.to_string(), call_span),
-    };
+        })?;
 
-    let full_path = match full_path.to_str() {
-        Some(p) => p,
-        None => return compile_error("fs_embed!: path must be valid UTF-8", call_span),
-    };
+    let full_path = full_path.to_str().ok_or_else(|| {
+        compile_error(format!("{macro_name}: path must be valid UTF-8"), call_span)
+    })?;
 
     if !full_path.starts_with(&manifest_dir) {
         let msg = format!(
-            "fs_embed!: directory not found:\n  {full_path}\n  expected to be inside crate root:\n  {manifest_dir}\n  relative path: {rel_path}",
+            "{macro_name}: path not found:\n  {full_path}\n  expected to be inside crate root:\n  {manifest_dir}\n  relative path: {rel_path}",
         );
-        return compile_error(&msg, call_span);
+        return Err(compile_error(&msg, call_span));
     };
 
-    let full_literal: LitStr = LitStr::new(full_path, call_span);
+    Ok(full_path.to_string())
+}
+
+/// Lists (lossily) every path below `root` whose relative path is not valid UTF-8, since
+/// those would make `File::file_name` and `path().to_str()` return `None` at runtime.
+fn non_utf8_paths(root: &Path) -> Vec<String> {
+    walkdir::WalkDir::new(root)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let rel = entry.path().strip_prefix(root).ok()?;
+            match rel.to_str() {
+                Some(_) => None,
+                None => Some(rel.to_string_lossy().into_owned()),
+            }
+        })
+        .collect()
+}
 
-    let embed_code = quote! {
-        ::fs_embed::Dir::from_embedded(include_dir::include_dir!(#full_literal), #full_literal)
+/// Returns true if any regular file exists below `root`, at any depth.
+fn has_files(root: &Path) -> bool {
+    walkdir::WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .flatten()
+        .any(|entry| entry.path().is_file())
+}
+
+/// Splits a mount prefix into its path segments, rejecting absolute or escaping paths.
+fn mount_segments(mount: &str) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    for segment in mount.split('/').filter(|s| !s.is_empty()) {
+        if segment == "." || segment == ".." || segment.contains('\\') {
+            return None;
+        }
+        segments.push(segment.to_string());
+    }
+    Some(segments)
+}
+
+/// The macro path as written, `/`-separated and without empty or `.` segments, for
+/// `File::source_location`.
+fn source_path(rel: &str) -> String {
+    rel.split(['/', '\\'])
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Nests the embedded root inside one directory per mount segment, so the
+/// mount prefix shows up as real directories in the embedded tree.
+fn wrap_mount(tree: proc_macro2::TokenStream, mount: &[String]) -> proc_macro2::TokenStream {
+    let mut wrapped = tree;
+    for depth in (0..mount.len()).rev() {
+        let path = mount[..depth].join("/");
+        wrapped = quote! {
+            include_dir::Dir::new(#path, {
+                const ENTRIES: &'static [include_dir::DirEntry<'static>] = &[include_dir::DirEntry::Dir(#wrapped)];
+                ENTRIES
+            })
+        };
+    }
+    wrapped
+}
+
+/// Generates an `include_dir::Dir` for `path`, with entries sorted by path
+/// and stored relative to `root` (prefixed by the mount segments).
+/// The sort is what makes the expansion reproducible: `read_dir` order is unspecified.
+fn expand_dir(
+    root: &Path,
+    path: &Path,
+    mount: &[String],
+    normalize_newlines: bool,
+    with_metadata: bool,
+) -> Result<proc_macro2::TokenStream, String> {
+    let mut children = Vec::new();
+    let read_dir = std::fs::read_dir(path)
+        .map_err(|e| format!("fs_embed!: unable to read {}: {e}", path.display()))?;
+    for entry in read_dir {
+        let entry =
+            entry.map_err(|e| format!("fs_embed!: unable to read {}: {e}", path.display()))?;
+        children.push(entry.path());
+    }
+    children.sort();
+
+    let mut child_tokens = Vec::new();
+    for child in children {
+        if child.is_dir() {
+            let tokens = expand_dir(root, &child, mount, normalize_newlines, with_metadata)?;
+            child_tokens.push(quote! { include_dir::DirEntry::Dir(#tokens) });
+        } else if child.is_file() {
+            let tokens = expand_file(root, &child, mount, normalize_newlines, with_metadata)?;
+            child_tokens.push(quote! { include_dir::DirEntry::File(#tokens) });
+        }
+    }
+
+    let rel = embedded_path(root, path, mount);
+    Ok(quote! {
+        include_dir::Dir::new(#rel, {
+            const ENTRIES: &'static [include_dir::DirEntry<'static>] = &[ #(#child_tokens),* ];
+            ENTRIES
+        })
+    })
+}
+
+fn expand_file(
+    root: &Path,
+    path: &Path,
+    mount: &[String],
+    normalize_newlines: bool,
+    with_metadata: bool,
+) -> Result<proc_macro2::TokenStream, String> {
+    let abs = path
+        .canonicalize()
+        .map_err(|e| format!("fs_embed!: failed to resolve {}: {e}", path.display()))?;
+    let read = || {
+        std::fs::read(path)
+            .map_err(|e| format!("fs_embed!: unable to read {}: {e}", path.display()))
+    };
+    let normalized = if normalize_newlines {
+        normalize_text(&read()?)
+    } else {
+        None
+    };
+    let contents = match (abs.to_str(), normalized) {
+        // Keep an `include_bytes!` around so cargo still rebuilds when the file changes.
+        (Some(abs), Some(bytes)) => {
+            let literal = proc_macro2::Literal::byte_string(&bytes);
+            quote!({
+                const _: &[u8] = include_bytes!(#abs);
+                #literal
+            })
+        }
+        (Some(abs), None) => quote!(include_bytes!(#abs)),
+        (None, normalized) => {
+            let bytes = match normalized {
+                Some(bytes) => bytes,
+                None => read()?,
+            };
+            let literal = proc_macro2::Literal::byte_string(&bytes);
+            quote!(#literal)
+        }
     };
 
-    quote! { #embed_code }.into()
+    let rel = embedded_path(root, path, mount);
+    let tokens = quote! { include_dir::File::new(#rel, #contents) };
+    Ok(match with_metadata.then(|| metadata(path)).flatten() {
+        Some(metadata) => quote!(#tokens.with_metadata(#metadata)),
+        None => tokens,
+    })
 }
 
+/// Rewrites `\r\n` to `\n` in text contents, returning `None` when nothing changes.
+/// Contents with a NUL byte are treated as binary and left untouched.
+fn normalize_text(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.contains(&0) || !bytes.windows(2).any(|w| w == b"\r\n") {
+        return None;
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().copied().peekable();
+    while let Some(byte) = iter.next() {
+        if byte == b'\r' && iter.peek() == Some(&b'\n') {
+            continue;
+        }
+        out.push(byte);
+    }
+    Some(out)
+}
+
+/// Only the modification time is read. Access and creation times change whenever the sources
+/// are read or checked out, so the modification time stands in for both.
+fn metadata(path: &Path) -> Option<proc_macro2::TokenStream> {
+    let modified = path
+        .metadata()
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+
+    Some(quote! {
+        include_dir::Metadata::new(
+            std::time::Duration::from_secs(#modified),
+            std::time::Duration::from_secs(#modified),
+            std::time::Duration::from_secs(#modified),
+        )
+    })
+}
 
+/// The `/`-separated path stored for an embedded entry: its path relative to the
+/// embedded root, prefixed with the mount segments.
+fn embedded_path(root: &Path, path: &Path, mount: &[String]) -> String {
+    let stripped = path.strip_prefix(root).unwrap_or(path);
+    mount
+        .iter()
+        .cloned()
+        .chain(
+            stripped
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
 /// Emit `compile_error!($msg)` at the given span.
 #[doc(hidden)]
@@ -71,11 +380,105 @@ fn compile_error<S: AsRef<str>>(msg: S, span: Span) -> TokenStream {
 
 struct EmbedArgs {
     path: Lit,
+    mount: Option<LitStr>,
+    allow_non_utf8: bool,
+    normalize_newlines: bool,
+    allow_empty: bool,
+    build_id: bool,
+    metadata: bool,
 }
 
 impl Parse for EmbedArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let path: Lit = input.parse()?;
-        Ok(EmbedArgs { path })
+        let mut mount = None;
+        let mut allow_non_utf8 = false;
+        let mut normalize_newlines = false;
+        let mut allow_empty = false;
+        let mut build_id = false;
+        let mut metadata = true;
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "mount" => mount = Some(input.parse::<LitStr>()?),
+                "allow_non_utf8" => allow_non_utf8 = input.parse::<LitBool>()?.value,
+                "normalize_newlines" => normalize_newlines = input.parse::<LitBool>()?.value,
+                "allow_empty" => allow_empty = input.parse::<LitBool>()?.value,
+                "build_id" => build_id = input.parse::<LitBool>()?.value,
+                "metadata" => metadata = input.parse::<LitBool>()?.value,
+                other => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("fs_embed!: unknown option `{other}`"),
+                    ));
+                }
+            }
+        }
+        Ok(EmbedArgs {
+            path,
+            mount,
+            allow_non_utf8,
+            normalize_newlines,
+            allow_empty,
+            build_id,
+            metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_dir;
+    use std::fs::{File, FileTimes};
+    use std::time::{Duration, SystemTime};
+
+    fn expand(root: &std::path::Path, with_metadata: bool) -> String {
+        expand_dir(root, root, &[], false, with_metadata)
+            .unwrap()
+            .to_string()
+    }
+
+    fn set_times(path: &std::path::Path, times: FileTimes) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(times)
+            .unwrap();
+    }
+
+    /// Checks that re-reading or re-checking-out the sources leaves the expansion unchanged
+    /// when it should, by expanding a scratch tree before and after touching its timestamps.
+    #[test]
+    fn test_expansion_ignores_volatile_timestamps() {
+        let root = std::env::temp_dir().join(format!("fs-embed-macros-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        let file = root.join("sub/a.txt");
+        std::fs::write(&file, b"a").unwrap();
+        let epoch = SystemTime::UNIX_EPOCH;
+        set_times(
+            &file,
+            FileTimes::new()
+                .set_accessed(epoch + Duration::from_secs(1))
+                .set_modified(epoch + Duration::from_secs(2)),
+        );
+        let with_metadata = expand(&root, true);
+        let without_metadata = expand(&root, false);
+
+        let accessed = FileTimes::new().set_accessed(epoch + Duration::from_secs(3));
+        set_times(&file, accessed);
+        assert_eq!(expand(&root, true), with_metadata);
+
+        let modified = FileTimes::new().set_modified(epoch + Duration::from_secs(4));
+        set_times(&file, modified);
+        assert_ne!(expand(&root, true), with_metadata);
+        assert_eq!(expand(&root, false), without_metadata);
+
+        std::fs::remove_dir_all(&root).unwrap();
     }
 }
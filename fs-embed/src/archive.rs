@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use crate::{Dir, File, InnerFile};
+
+/// Mode used for embedded and in-memory files, which carry no permission bits.
+const DEFAULT_MODE: u32 = 0o644;
+
+impl Dir {
+    /// Streams every file in this directory into a tar archive written to `w`.
+    /// Entries use the file's relative path and modification time, or the Unix epoch for
+    /// embedded files built without timestamps. The archive is written sequentially, so it is
+    /// never buffered in memory as a whole.
+    /// Unix mode bits are preserved for files read from disk.
+    pub fn write_tar<W: Write>(&self, w: W) -> std::io::Result<()> {
+        let mut builder = tar::Builder::new(w);
+        for file in self.walk() {
+            append_file(&mut builder, &file)?;
+        }
+        builder.into_inner()?.flush()
+    }
+}
+
+fn append_file<W: Write>(builder: &mut tar::Builder<W>, file: &File) -> std::io::Result<()> {
+    let metadata = file.metadata_or_epoch()?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(metadata.size);
+    header.set_mode(file_mode(file));
+    header.set_mtime(
+        metadata
+            .modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    );
+    match &file.inner {
+        InnerFile::Embed(embedded, ..) => {
+            builder.append_data(&mut header, file.path(), embedded.contents())
+        }
+        InnerFile::Memory { file: memory, .. } => {
+            builder.append_data(&mut header, file.path(), memory.contents.as_slice())
+        }
+        InnerFile::Path { path, .. } => {
+            builder.append_data(&mut header, file.path(), std::fs::File::open(path)?)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(file: &File) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    match &file.inner {
+        InnerFile::Embed(..) | InnerFile::Memory { .. } => DEFAULT_MODE,
+        InnerFile::Path { path, .. } => std::fs::metadata(path)
+            .map(|m| m.permissions().mode() & 0o7777)
+            .unwrap_or(DEFAULT_MODE),
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_file: &File) -> u32 {
+    DEFAULT_MODE
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use crate::{DirSet, InnerFile};
+
+/// A [`DirSet`] wrapper that memoizes file contents read from disk.
+///
+/// Cached entries are keyed by relative path and evicted least-recently-used once the
+/// total cached size exceeds the byte budget. An entry is re-read whenever the file's
+/// modification time or size changes, so edits are still picked up. Embedded files are
+/// already in memory and bypass the cache entirely.
+#[derive(Debug)]
+pub struct CachedDirSet {
+    set: DirSet,
+    max_bytes: usize,
+    state: Mutex<CacheState>,
+}
+
+/// Contents returned by [`CachedDirSet::read_bytes`]. Dereferences to `[u8]`.
+#[derive(Debug, Clone)]
+pub enum CachedBytes {
+    /// Embedded contents, borrowed from the binary without copying.
+    Static(&'static [u8]),
+    /// Contents read from disk, shared with the cache.
+    Shared(Arc<[u8]>),
+}
+
+impl std::ops::Deref for CachedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            CachedBytes::Static(bytes) => bytes,
+            CachedBytes::Shared(bytes) => bytes,
+        }
+    }
+}
+
+impl AsRef<[u8]> for CachedBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: usize,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    modified: SystemTime,
+    bytes: Arc<[u8]>,
+    last_used: u64,
+}
+
+impl CachedDirSet {
+    /// Wraps `set`, caching at most `max_bytes` of file contents.
+    pub fn new(set: DirSet, max_bytes: usize) -> Self {
+        Self {
+            set,
+            max_bytes,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Returns the wrapped [`DirSet`].
+    pub fn dir_set(&self) -> &DirSet {
+        &self.set
+    }
+
+    /// Returns the total size in bytes of the currently cached contents.
+    pub fn cached_bytes(&self) -> usize {
+        self.lock().total_bytes
+    }
+
+    /// Drops every cached entry.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.total_bytes = 0;
+    }
+
+    /// Reads the highest-precedence file with the given name, serving it from the cache
+    /// when the file is unchanged on disk. Embedded files are returned as
+    /// [`CachedBytes::Static`] without copying or touching the cache.
+    /// Returns an error of kind `NotFound` if no root contains the file.
+    pub fn read_bytes(&self, name: &str) -> std::io::Result<CachedBytes> {
+        let file = self.set.get_file(name).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("file not found: {name}"),
+            )
+        })?;
+        if let InnerFile::Embed(embedded, ..) = &file.inner {
+            return Ok(CachedBytes::Static(embedded.contents()));
+        }
+
+        let metadata = file.metadata()?;
+        let key = file.path().to_string_lossy().into_owned();
+        {
+            let mut state = self.lock();
+            state.tick += 1;
+            let tick = state.tick;
+            if let Some(entry) = state.entries.get_mut(&key)
+                && entry.modified == metadata.modified
+                && entry.bytes.len() as u64 == metadata.size
+            {
+                entry.last_used = tick;
+                return Ok(CachedBytes::Shared(entry.bytes.clone()));
+            }
+        }
+
+        let bytes: Arc<[u8]> = Arc::from(file.read_bytes()?);
+        self.insert(key, metadata.modified, bytes.clone());
+        Ok(CachedBytes::Shared(bytes))
+    }
+
+    fn insert(&self, key: String, modified: SystemTime, bytes: Arc<[u8]>) {
+        let mut state = self.lock();
+        if let Some(old) = state.entries.remove(&key) {
+            state.total_bytes -= old.bytes.len();
+        }
+        if bytes.len() > self.max_bytes {
+            return;
+        }
+        while state.total_bytes + bytes.len() > self.max_bytes {
+            let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = state.entries.remove(&oldest) {
+                state.total_bytes -= evicted.bytes.len();
+            }
+        }
+        state.tick += 1;
+        let last_used = state.tick;
+        state.total_bytes += bytes.len();
+        state.entries.insert(
+            key,
+            CacheEntry {
+                modified,
+                bytes,
+                last_used,
+            },
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
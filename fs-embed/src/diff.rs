@@ -0,0 +1,48 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{DirSet, File};
+
+/// The differences between two [`DirSet`]s, as returned by [`diff`]. Each list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirDiff {
+    /// Paths only present in the new set.
+    pub added: Vec<PathBuf>,
+    /// Paths only present in the old set.
+    pub removed: Vec<PathBuf>,
+    /// Paths present in both sets whose contents differ.
+    pub changed: Vec<PathBuf>,
+}
+
+impl DirDiff {
+    /// Returns true if the two sets hold the same files with the same contents.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two layered sets after resolving each with [`DirSet::walk_override`], reporting
+/// which relative paths were added, removed or changed. Contents are compared by SHA-256, so
+/// an embedded file and its unchanged copy on disk compare equal.
+pub fn diff(old: &DirSet, new: &DirSet) -> std::io::Result<DirDiff> {
+    let old = resolve(old);
+    let mut new = resolve(new);
+    let mut result = DirDiff::default();
+    for (path, old_file) in old {
+        match new.remove(&path) {
+            Some(new_file) => {
+                if old_file.sha256()? != new_file.sha256()? {
+                    result.changed.push(path);
+                }
+            }
+            None => result.removed.push(path),
+        }
+    }
+    result.added = new.into_keys().collect();
+    Ok(result)
+}
+
+fn resolve(set: &DirSet) -> BTreeMap<PathBuf, File> {
+    set.walk_override()
+        .map(|file| (file.path().to_path_buf(), file))
+        .collect()
+}
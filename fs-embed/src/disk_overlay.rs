@@ -0,0 +1,110 @@
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use crate::{Dir, DirEntry, File, InnerDir, InnerEntry, InnerFile, Origin};
+
+impl Dir {
+    /// Returns a view of this directory that prefers files found under `disk_root` and falls
+    /// back to this directory, one file at a time.
+    ///
+    /// `disk_root` mirrors the layout of the tree this directory belongs to, so dropping
+    /// `disk_root/templates/index.html` next to the binary patches just that template while
+    /// every other file is still served from the embedded copy. Files and subdirectories that
+    /// only exist on disk are visible too. Lookups check the disk on every call, so patches
+    /// are picked up without a restart.
+    pub fn with_disk_overlay(self, disk_root: &Path) -> Dir {
+        Dir {
+            inner: InnerDir::DiskOverlay {
+                base: Arc::new(self.inner),
+                disk_root: Arc::from(disk_root),
+            },
+        }
+    }
+}
+
+/// Lists `base`, swapping in files that exist on disk and adding entries only found there.
+pub(crate) fn entries(base: &InnerDir, disk_root: &Arc<Path>) -> Vec<DirEntry> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for entry in (Dir {
+        inner: base.clone(),
+    })
+    .entries()
+    {
+        let inner = match entry.inner {
+            InnerEntry::File(file) => {
+                seen.insert(file.path().to_path_buf());
+                InnerEntry::File(disk_file(disk_root, file.path()).unwrap_or(file))
+            }
+            InnerEntry::Dir(dir) => {
+                seen.insert(dir.path().to_path_buf());
+                InnerEntry::Dir(wrap(dir, disk_root))
+            }
+        };
+        entries.push(DirEntry { inner });
+    }
+
+    let on_disk = Dir {
+        inner: disk_dir(disk_root, base.path()),
+    };
+    for entry in on_disk.entries() {
+        if seen.insert(entry.path().to_path_buf()) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+pub(crate) fn get_file(base: &InnerDir, disk_root: &Arc<Path>, name: &str) -> Option<File> {
+    let on_disk = Dir {
+        inner: disk_dir(disk_root, base.path()),
+    };
+    on_disk.get_file(name).or_else(|| {
+        (Dir {
+            inner: base.clone(),
+        })
+        .get_file(name)
+    })
+}
+
+pub(crate) fn get_dir(base: &InnerDir, disk_root: &Arc<Path>, name: &str) -> Option<Dir> {
+    match (Dir {
+        inner: base.clone(),
+    })
+    .get_dir(name)
+    {
+        Some(dir) => Some(Dir {
+            inner: wrap(dir.inner, disk_root),
+        }),
+        None => Dir {
+            inner: disk_dir(disk_root, base.path()),
+        }
+        .get_dir(name),
+    }
+}
+
+fn wrap(dir: InnerDir, disk_root: &Arc<Path>) -> InnerDir {
+    InnerDir::DiskOverlay {
+        base: Arc::new(dir),
+        disk_root: disk_root.clone(),
+    }
+}
+
+/// The on-disk counterpart of the directory at relative path `rel`, which may not exist.
+fn disk_dir(disk_root: &Arc<Path>, rel: &Path) -> InnerDir {
+    InnerDir::Path {
+        root: disk_root.clone(),
+        path: Arc::from(disk_root.join(rel)),
+        origin: Origin::Filesystem,
+        mount: None,
+    }
+}
+
+fn disk_file(disk_root: &Arc<Path>, rel: &Path) -> Option<InnerFile> {
+    let path = disk_root.join(rel);
+    path.is_file().then(|| InnerFile::Path {
+        root: disk_root.clone(),
+        path,
+        origin: Origin::Filesystem,
+        mounted: None,
+    })
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use crate::{Dir, DirEntry, File, InnerDir, InnerEntry};
+
+/// A shared file predicate installed by [`Dir::filter`].
+#[derive(Clone)]
+pub(crate) struct FilePredicate(Arc<dyn Fn(&File) -> bool + Send + Sync>);
+
+impl std::fmt::Debug for FilePredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FilePredicate(..)")
+    }
+}
+
+/// Whether each directory in a filtered subtree contains a matching file, keyed by relative
+/// path. Filled in one pass over the subtree and shared by the subdirectories listed from it,
+/// so walking a filtered tree lists every directory at most twice.
+pub(crate) type Visible = Arc<HashMap<PathBuf, bool>>;
+
+impl FilePredicate {
+    /// Returns true if both are the same installed predicate.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Dir {
+    /// Returns a view of this directory that only exposes files for which `predicate` returns
+    /// true. Subdirectories without any matching file are hidden as well.
+    ///
+    /// The filter is applied lazily on every lookup and walk, and the result is an ordinary
+    /// [`Dir`], so it can be placed in a [`DirSet`](crate::DirSet) or converted with
+    /// [`Dir::into_dynamic`]. Listing a directory checks each subdirectory's subtree once, and
+    /// the subdirectories it returns remember which of their own subdirectories matched, so a
+    /// walk stays linear in the size of the tree.
+    pub fn filter(&self, predicate: impl Fn(&File) -> bool + Send + Sync + 'static) -> Dir {
+        Dir {
+            inner: InnerDir::Filter {
+                base: Arc::new(self.inner.clone()),
+                predicate: FilePredicate(Arc::new(predicate)),
+                visible: None,
+            },
+        }
+    }
+}
+
+/// Lists `base`, keeping matching files and the subdirectories that contain one.
+pub(crate) fn entries(
+    base: &InnerDir,
+    predicate: &FilePredicate,
+    visible: Option<&Visible>,
+) -> Vec<DirEntry> {
+    let unknown = Visible::default();
+    let visible = visible.unwrap_or(&unknown);
+    (Dir {
+        inner: base.clone(),
+    })
+    .entries()
+    .into_iter()
+    .filter_map(|entry| match entry.inner {
+        InnerEntry::File(file) => {
+            let file = File { inner: file };
+            (predicate.0)(&file).then(|| DirEntry::from_file(file))
+        }
+        InnerEntry::Dir(dir) => wrap(dir, predicate, visible).map(DirEntry::from_dir),
+    })
+    .collect()
+}
+
+pub(crate) fn get_file(base: &InnerDir, predicate: &FilePredicate, name: &str) -> Option<File> {
+    (Dir {
+        inner: base.clone(),
+    })
+    .get_file(name)
+    .filter(|file| (predicate.0)(file))
+}
+
+pub(crate) fn get_dir(
+    base: &InnerDir,
+    predicate: &FilePredicate,
+    visible: Option<&Visible>,
+    name: &str,
+) -> Option<Dir> {
+    let dir = (Dir {
+        inner: base.clone(),
+    })
+    .get_dir(name)?;
+    wrap(dir.inner, predicate, visible.unwrap_or(&Visible::default()))
+}
+
+/// Applies the filter to a subdirectory, or returns `None` if nothing in it matches. A
+/// subdirectory missing from `visible` has its whole subtree checked once.
+fn wrap(dir: InnerDir, predicate: &FilePredicate, visible: &Visible) -> Option<Dir> {
+    let (shown, visible) = match visible.get(dir.path()) {
+        Some(shown) => (*shown, visible.clone()),
+        None => {
+            let mut memo = HashMap::new();
+            let shown = check(&Dir { inner: dir.clone() }, predicate, &mut memo, 0);
+            (shown, Arc::new(memo))
+        }
+    };
+    shown.then(|| Dir {
+        inner: InnerDir::Filter {
+            base: Arc::new(dir),
+            predicate: predicate.clone(),
+            visible: Some(visible),
+        },
+    })
+}
+
+/// Records in `memo` whether `dir`, found `depth` levels down, and each directory below it
+/// contain a matching file, and returns the answer for `dir`.
+fn check(
+    dir: &Dir,
+    predicate: &FilePredicate,
+    memo: &mut HashMap<PathBuf, bool>,
+    depth: usize,
+) -> bool {
+    let mut shown = false;
+    for (entry, depth) in dir.child_entries(depth) {
+        match entry.inner {
+            InnerEntry::File(file) => shown = shown || (predicate.0)(&File { inner: file }),
+            InnerEntry::Dir(subdir) => {
+                shown |= check(&Dir { inner: subdir }, predicate, memo, depth);
+            }
+        }
+    }
+    memo.insert(dir.path().to_path_buf(), shown);
+    shown
+}
@@ -0,0 +1,163 @@
+use ::http::{HeaderMap, HeaderValue, Method, Response, StatusCode, header};
+
+use crate::{DirSet, File, InnerFile};
+
+/// A response body produced by [`respond`].
+#[derive(Debug)]
+pub enum Body {
+    /// No body, for `HEAD`, `304` and error responses.
+    Empty,
+    /// Embedded contents, served without copying.
+    Static(&'static [u8]),
+    /// Contents held in memory, as added with [`Dir::with_file`](crate::Dir::with_file).
+    Owned(Vec<u8>),
+    /// An open file on disk, for the caller to stream.
+    File(std::fs::File),
+}
+
+impl Body {
+    /// Reads the whole body into memory.
+    pub fn into_bytes(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Body::Empty => Ok(Vec::new()),
+            Body::Static(bytes) => Ok(bytes.to_vec()),
+            Body::Owned(bytes) => Ok(bytes),
+            Body::File(mut file) => {
+                let mut bytes = Vec::new();
+                std::io::Read::read_to_end(&mut file, &mut bytes)?;
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// Serves `path` from `set` as a ready [`http::Response`](::http::Response), independent of any
+/// web framework.
+///
+/// Handles `GET` and `HEAD` (anything else is `405` with an `Allow` header), looks the
+/// percent-decoded path up with override precedence (`404` if missing), and sets
+/// `Content-Type`, `Content-Length`, `ETag` and `Last-Modified`. A matching `If-None-Match`,
+/// or an `If-Modified-Since` no older than the file when `If-None-Match` is absent, yields `304`.
+/// Embedded files get a strong [`File::etag`], hashed once per process; dynamic ones a cheaper
+/// [`File::weak_etag`]. The `Content-Type` comes from [`File::content_type`]; use
+/// [`respond_with`] to add or replace mappings.
+///
+/// `path` is untrusted: after decoding, anything other than plain `/`-separated names below
+/// the root (`..` or `.` segments, empty segments such as `//`, backslashes or drive
+/// prefixes) is answered with `404` without touching the filesystem.
+pub fn respond(set: &DirSet, method: &Method, path: &str, headers: &HeaderMap) -> Response<Body> {
+    respond_with(set, method, path, headers, &phf::Map::new())
+}
+
+/// Like [`respond`], but picks the `Content-Type` with [`File::content_type_with`], so
+/// `overrides` can add or replace extension mappings, e.g. `"wasm" => "application/wasm"`.
+pub fn respond_with(
+    set: &DirSet,
+    method: &Method,
+    path: &str,
+    headers: &HeaderMap,
+    overrides: &phf::Map<&'static str, &'static str>,
+) -> Response<Body> {
+    if method != Method::GET && method != Method::HEAD {
+        let mut response = status(StatusCode::METHOD_NOT_ALLOWED);
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static("GET, HEAD"));
+        return response;
+    }
+    let file = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()
+        .and_then(|decoded| request_path(&decoded).map(str::to_owned))
+        .and_then(|name| set.get_file(&name));
+    let Some(file) = file else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    match respond_file(&file, method, headers, overrides) {
+        Ok(response) => response,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => status(StatusCode::NOT_FOUND),
+        Err(_) => status(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+fn respond_file(
+    file: &File,
+    method: &Method,
+    headers: &HeaderMap,
+    overrides: &phf::Map<&'static str, &'static str>,
+) -> std::io::Result<Response<Body>> {
+    let metadata = file.metadata_or_epoch()?;
+    let etag = if file.is_embedded() {
+        file.etag()?
+    } else {
+        file.weak_etag()?
+    };
+    let last_modified = httpdate::HttpDate::from(metadata.modified);
+
+    let not_modified = match headers.get(header::IF_NONE_MATCH) {
+        Some(value) => value.to_str().is_ok_and(|value| etag_matches(value, &etag)),
+        None => headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<httpdate::HttpDate>().ok())
+            .is_some_and(|since| last_modified <= since),
+    };
+
+    let mut response = if not_modified {
+        status(StatusCode::NOT_MODIFIED)
+    } else if method == Method::HEAD {
+        Response::new(Body::Empty)
+    } else {
+        Response::new(body(file)?)
+    };
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&last_modified.to_string()) {
+        response_headers.insert(header::LAST_MODIFIED, value);
+    }
+    if !not_modified {
+        let content_type = file
+            .content_type_with(overrides)
+            .unwrap_or("application/octet-stream");
+        response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(metadata.size));
+    }
+    Ok(response)
+}
+
+/// Strips the single leading `/` of a decoded request path, refusing any path that is not a
+/// plain sequence of names.
+fn request_path(decoded: &str) -> Option<&str> {
+    let name = decoded.strip_prefix('/').unwrap_or(decoded);
+    let plain = name.split('/').all(|segment| {
+        let mut components = std::path::Path::new(segment).components();
+        !segment.contains('\\')
+            && matches!(components.next(), Some(std::path::Component::Normal(_)))
+            && components.next().is_none()
+    });
+    plain.then_some(name)
+}
+
+fn body(file: &File) -> std::io::Result<Body> {
+    match &file.inner {
+        InnerFile::Embed(embedded, ..) => Ok(Body::Static(embedded.contents())),
+        InnerFile::Memory { file, .. } => Ok(Body::Owned(file.contents.clone())),
+        InnerFile::Path { path, .. } => std::fs::File::open(path)
+            .map(Body::File)
+            .map_err(|e| file.path_error(e)),
+    }
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::Empty);
+    *response.status_mut() = code;
+    response
+}
+
+/// Weak comparison of an `If-None-Match` list against `etag`, as required for `GET`.
+fn etag_matches(header: &str, etag: &str) -> bool {
+    let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header.trim() == "*" || header.split(',').any(|tag| strip(tag) == strip(etag))
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::{Dir, File, lookup_name, normalize_path};
+
+/// A prebuilt lookup table from relative path to [`File`], created by [`Dir::index`].
+///
+/// Keys are `/`-separated paths relative to the indexed directory, so lookups accept the
+/// same names as [`Dir::get_file`] but cost a single hash-map probe.
+#[derive(Debug, Clone, Default)]
+pub struct DirIndex {
+    files: HashMap<String, File>,
+}
+
+impl DirIndex {
+    /// Returns the file with the given relative path, if it was present when the index was built.
+    /// Names are resolved like [`Dir::get_file`]: `..` removes the previous segment, and a name
+    /// that would leave the indexed directory returns `None`.
+    pub fn get(&self, name: &str) -> Option<&File> {
+        match self.files.get(name.strip_prefix('/').unwrap_or(name)) {
+            Some(file) => Some(file),
+            None => self.files.get(&lookup_name(name)?),
+        }
+    }
+
+    /// Returns the number of indexed files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if no files were indexed.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl Dir {
+    /// Walks this directory once and builds a [`DirIndex`] for O(1) repeated lookups.
+    /// The index is a snapshot: files added to a dynamic directory later are not seen.
+    pub fn index(&self) -> DirIndex {
+        let files = self
+            .walk()
+            .map(|file| {
+                let rel = file.path().strip_prefix(self.path()).unwrap_or(file.path());
+                (normalize_path(rel), file)
+            })
+            .collect();
+        DirIndex { files }
+    }
+}
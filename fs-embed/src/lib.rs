@@ -1,6 +1,39 @@
-use std::{collections::VecDeque, path::PathBuf};
+use sha2::Digest;
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
 
-pub use fs_embed_macros::fs_embed;
+pub use fs_embed_macros::{fs_embed, fs_embed_file};
+
+#[cfg(feature = "tar")]
+mod archive;
+mod cache;
+mod diff;
+mod disk_overlay;
+mod filter;
+#[cfg(feature = "http")]
+pub mod http;
+mod index;
+mod mime;
+mod mount;
+mod overlay;
+mod preload;
+#[cfg(feature = "bytes")]
+mod shared;
+mod source;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod validate;
+
+pub use cache::{CachedBytes, CachedDirSet};
+pub use diff::{DirDiff, diff};
+pub use index::DirIndex;
+pub use mime::DEFAULT_MIME_TYPES;
+pub use preload::{PreloadedFile, PreloadedSet};
+pub use source::{FileLike, Source};
+pub use validate::ValidationIssue;
+
+/// The number of directory levels [`Dir::walk`] and the other recursive walks descend before
+/// they stop.
+pub const DEFAULT_WALK_DEPTH: usize = 64;
 
 pub struct FileMetaData {
     /// The last modification time of the file.
@@ -9,12 +42,75 @@ pub struct FileMetaData {
     pub size: u64,
 }
 
+/// A filesystem error annotated with the file it concerns.
+///
+/// Reads of dynamic [`File`]s wrap their I/O errors in this type, so the message names both
+/// the relative and absolute path while [`std::io::Error::kind`] stays unchanged. Recover it with
+/// `err.get_ref().and_then(|e| e.downcast_ref::<PathError>())`.
+#[derive(Debug, thiserror::Error)]
+#[error("{}: {source} ({})", relative.display(), absolute.display())]
+pub struct PathError {
+    relative: PathBuf,
+    absolute: PathBuf,
+    source: std::io::Error,
+}
+
+impl PathError {
+    /// Returns the path of the file relative to its root.
+    pub fn relative_path(&self) -> &std::path::Path {
+        &self.relative
+    }
+
+    /// Returns the absolute path of the file.
+    pub fn absolute_path(&self) -> &std::path::Path {
+        &self.absolute
+    }
+}
+
+/// Where a directory or file originally came from.
+/// Unlike [`Dir::is_embedded`], this is preserved across [`Dir::into_dynamic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// Compiled into the binary with `fs_embed!`, even if now read from disk.
+    Embedded,
+    /// Created from a filesystem path.
+    Filesystem,
+}
+
+/// What `fs_embed!` recorded about an embedded tree, shared by every directory and file in it.
+#[derive(Debug, Clone, Copy)]
+struct EmbedRoot {
+    /// The on-disk directory the tree was embedded from.
+    path: &'static str,
+    /// The path as written in the `fs_embed!` call, relative to the crate root and
+    /// `/`-separated, if known.
+    source: Option<&'static str>,
+    /// The `/`-separated `mount` prefix the tree is nested under, or empty.
+    mount: &'static str,
+    build_id: Option<&'static str>,
+}
+
 #[derive(Debug, Clone)]
 enum InnerFile {
-    Embed(include_dir::File<'static>),
+    /// An embedded file, an embedded directory containing it (used to find its siblings) and
+    /// the tree it was embedded with.
+    Embed(
+        &'static include_dir::File<'static>,
+        include_dir::Dir<'static>,
+        EmbedRoot,
+    ),
     Path {
-        root: std::path::PathBuf,
+        root: Arc<std::path::Path>,
         path: std::path::PathBuf,
+        origin: Origin,
+        /// The relative path including the `mount` prefix, for the dynamic counterpart of a
+        /// mounted embed; otherwise the path relative to `root` is used.
+        mounted: Option<PathBuf>,
+    },
+    Memory {
+        path: std::path::PathBuf,
+        file: Arc<overlay::MemoryFile>,
+        origin: Origin,
     },
 }
 
@@ -36,31 +132,64 @@ impl InnerFile {
     #[inline(always)]
     fn absolute_path(&self) -> &std::path::Path {
         match self {
-            InnerFile::Embed(file) => file.path(),
+            InnerFile::Embed(file, ..) => file.path(),
             InnerFile::Path { path, .. } => path.as_path(),
+            InnerFile::Memory { path, .. } => path.as_path(),
         }
     }
 
     #[inline(always)]
     fn is_embedded(&self) -> bool {
-        matches!(self, InnerFile::Embed(_))
+        matches!(self, InnerFile::Embed(..))
+    }
+
+    #[inline(always)]
+    fn origin(&self) -> Origin {
+        match self {
+            InnerFile::Embed(..) => Origin::Embedded,
+            InnerFile::Path { origin, .. } | InnerFile::Memory { origin, .. } => *origin,
+        }
     }
 
     #[inline(always)]
     pub fn path(&self) -> &std::path::Path {
         match self {
-            InnerFile::Embed(dir) => dir.path(),
-            InnerFile::Path { root, path } => path.strip_prefix(root).unwrap_or(path),
+            InnerFile::Embed(dir, ..) => dir.path(),
+            InnerFile::Path {
+                mounted: Some(rel), ..
+            } => rel,
+            InnerFile::Path { root, path, .. } => path.strip_prefix(root).unwrap_or(path),
+            InnerFile::Memory { path, .. } => path.as_path(),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 enum InnerDir {
-    Embed(include_dir::Dir<'static>, &'static str),
+    /// An embedded tree and what was recorded about it at build time.
+    Embed(include_dir::Dir<'static>, EmbedRoot),
     Path {
-        root: std::path::PathBuf,
-        path: std::path::PathBuf,
+        root: Arc<std::path::Path>,
+        path: Arc<std::path::Path>,
+        origin: Origin,
+        /// Where this directory sits below a `mount` prefix, for the dynamic counterpart of
+        /// a mounted embed.
+        mount: Option<mount::Mount>,
+    },
+    Overlay {
+        base: Arc<InnerDir>,
+        files: overlay::OverlayFiles,
+    },
+    Filter {
+        base: Arc<InnerDir>,
+        predicate: filter::FilePredicate,
+        /// Which directories below this one contain a match, worked out when this directory
+        /// was listed by its filtered parent. `None` for the view [`Dir::filter`] returns.
+        visible: Option<filter::Visible>,
+    },
+    DiskOverlay {
+        base: Arc<InnerDir>,
+        disk_root: Arc<std::path::Path>,
     },
 }
 
@@ -81,32 +210,127 @@ impl std::hash::Hash for InnerDir {
 impl InnerDir {
     fn into_dynamic(self) -> Self {
         match &self {
-            InnerDir::Embed(dir, path) => Self::Path {
-                root: PathBuf::from(path),
-                path: PathBuf::from(path).join(dir.path()),
-            },
+            InnerDir::Embed(dir, root) => mount::dynamic(root, dir.path()),
             InnerDir::Path { .. } => self,
+            InnerDir::Overlay { base, files } => Self::Overlay {
+                base: Arc::new(InnerDir::clone(base).into_dynamic()),
+                files: files.clone(),
+            },
+            InnerDir::Filter {
+                base, predicate, ..
+            } => Self::Filter {
+                base: Arc::new(InnerDir::clone(base).into_dynamic()),
+                predicate: predicate.clone(),
+                visible: None,
+            },
+            InnerDir::DiskOverlay { base, disk_root } => Self::DiskOverlay {
+                base: Arc::new(InnerDir::clone(base).into_dynamic()),
+                disk_root: disk_root.clone(),
+            },
         }
     }
 
     #[inline(always)]
     fn is_embedded(&self) -> bool {
-        matches!(self, InnerDir::Embed(..))
+        match self {
+            InnerDir::Embed(..) => true,
+            InnerDir::Path { .. } => false,
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.is_embedded(),
+        }
+    }
+
+    #[inline(always)]
+    fn origin(&self) -> Origin {
+        match self {
+            InnerDir::Embed(..) => Origin::Embedded,
+            InnerDir::Path { origin, .. } => *origin,
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.origin(),
+        }
     }
 
     #[inline(always)]
     fn path(&self) -> &std::path::Path {
         match self {
-            InnerDir::Embed(dir, _) => dir.path(),
-            InnerDir::Path { root, path } => path.strip_prefix(root).unwrap_or(path),
+            InnerDir::Embed(dir, ..) => dir.path(),
+            InnerDir::Path {
+                mount: Some(mount), ..
+            } => &mount.rel,
+            InnerDir::Path { root, path, .. } => path.strip_prefix(root).unwrap_or(path),
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.path(),
+        }
+    }
+
+    #[inline(always)]
+    fn root(&self) -> &std::path::Path {
+        match self {
+            InnerDir::Embed(_, root) => std::path::Path::new(root.path),
+            InnerDir::Path { root, .. } => root,
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.root(),
+        }
+    }
+
+    /// Compares the backend and every wrapper, ignoring the relative path. Overlay files and
+    /// filter predicates are compared by identity, so only clones of one view match.
+    fn same_layer(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                InnerDir::Embed(..) | InnerDir::Path { .. },
+                InnerDir::Embed(..) | InnerDir::Path { .. },
+            ) => self.is_embedded() == other.is_embedded() && self.root() == other.root(),
+            (
+                InnerDir::Overlay { base, files },
+                InnerDir::Overlay {
+                    base: other_base,
+                    files: other_files,
+                },
+            ) => files.ptr_eq(other_files) && base.same_layer(other_base),
+            (
+                InnerDir::Filter {
+                    base, predicate, ..
+                },
+                InnerDir::Filter {
+                    base: other_base,
+                    predicate: other_predicate,
+                    ..
+                },
+            ) => predicate.ptr_eq(other_predicate) && base.same_layer(other_base),
+            (
+                InnerDir::DiskOverlay { base, disk_root },
+                InnerDir::DiskOverlay {
+                    base: other_base,
+                    disk_root: other_root,
+                },
+            ) => disk_root == other_root && base.same_layer(other_base),
+            _ => false,
+        }
+    }
+
+    fn build_id(&self) -> Option<&'static str> {
+        match self {
+            InnerDir::Embed(_, root) => root.build_id,
+            InnerDir::Path { .. } => None,
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.build_id(),
         }
     }
 
     #[inline(always)]
     fn absolute_path(&self) -> &std::path::Path {
         match self {
-            InnerDir::Embed(dir, _) => dir.path(),
-            InnerDir::Path { path, .. } => path.as_path(),
+            InnerDir::Embed(dir, ..) => dir.path(),
+            InnerDir::Path { path, .. } => path,
+            InnerDir::Overlay { base, .. }
+            | InnerDir::Filter { base, .. }
+            | InnerDir::DiskOverlay { base, .. } => base.absolute_path(),
         }
     }
 }
@@ -147,8 +371,13 @@ impl std::hash::Hash for InnerEntry {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Represents a directory, which may be embedded or from the filesystem.
 /// Provides methods to enumerate and access files and subdirectories.
-/// Represents a directory, which may be embedded or from the filesystem.
-/// Provides methods to enumerate and access files and subdirectories.
+///
+/// Equality and hashing are based on the relative path only, so the same subdirectory
+/// in two different layers compares equal; this is what overlay lookups rely on.
+/// Use [`Dir::same_root`] to tell apart directories backed by different roots.
+///
+/// Cloning is O(1) for every backend: dynamic paths and in-memory overlays are shared
+/// behind an `Arc` rather than copied.
 pub struct Dir {
     inner: InnerDir,
 }
@@ -156,45 +385,167 @@ pub struct Dir {
 impl Dir {
     /// Creates a directory from an embedded `include_dir::Dir` and its root path.
     /// Intended for use in tests and advanced scenarios.
+    ///
+    /// `path` is the on-disk directory the embedded tree was built from. It is only used by
+    /// [`Dir::into_dynamic`]; embedded lookups always resolve against the paths stored by
+    /// `include_dir`, which are relative to the embedded root (so a subdirectory keeps its
+    /// full relative path, and names passed to `get_file` are joined onto it).
     pub const fn from_embedded(dir: include_dir::Dir<'static>, path: &'static str) -> Self {
+        Self::from_embedded_parts(dir, path, None, "", None)
+    }
+
+    /// Like [`Dir::from_embedded`], but also records a build id, as `fs_embed!` does with
+    /// `build_id = true`. See [`Dir::build_id`].
+    pub const fn from_embedded_with_build_id(
+        dir: include_dir::Dir<'static>,
+        path: &'static str,
+        build_id: &'static str,
+    ) -> Self {
+        Self::from_embedded_parts(dir, path, None, "", Some(build_id))
+    }
+
+    /// Creates an embedded directory with every option `fs_embed!` can record: the on-disk
+    /// root, the crate-relative path written in the macro call, the `/`-separated `mount`
+    /// prefix the tree is nested under (or `""`), and the build id. Generated by the macro;
+    /// prefer the other constructors in hand-written code.
+    #[doc(hidden)]
+    pub const fn from_embedded_parts(
+        dir: include_dir::Dir<'static>,
+        path: &'static str,
+        source: Option<&'static str>,
+        mount: &'static str,
+        build_id: Option<&'static str>,
+    ) -> Self {
         Self {
-            inner: InnerDir::Embed(dir, path),
+            inner: InnerDir::Embed(
+                dir,
+                EmbedRoot {
+                    path,
+                    source,
+                    mount,
+                    build_id,
+                },
+            ),
         }
     }
 
+    /// Returns the build id captured by `fs_embed!(.., build_id = true)`: the value of the
+    /// `FS_EMBED_BUILD_ID` environment variable at compile time (e.g. a git commit set from a
+    /// build script), or else the build's Unix timestamp in seconds. Append it to asset URLs to
+    /// bust caches on deploy. Subdirectories inherit it; dynamic directories return `None`.
+    pub fn build_id(&self) -> Option<&'static str> {
+        self.inner.build_id()
+    }
+
     /// Creates a new directory from the given path, relative to the manifest directory at build time.
     /// The path can be any valid subdirectory or file path.
+    ///
+    /// The path is not checked: a missing directory simply looks empty, which suits optional
+    /// or lazily created directories. Use [`Dir::from_path_checked`] to fail fast instead.
     pub fn from_path(path: &std::path::Path) -> Self {
-        const BASE_DIR: &'static str = env!("CARGO_MANIFEST_DIR");
-        let base_path = std::path::PathBuf::from(BASE_DIR);
+        const BASE_DIR: &str = env!("CARGO_MANIFEST_DIR");
+        let full_path: Arc<std::path::Path> = Arc::from(std::path::Path::new(BASE_DIR).join(path));
         Self {
             inner: InnerDir::Path {
-                root: base_path.join(path),
-                path: base_path.join(path),
+                root: full_path.clone(),
+                path: full_path,
+                origin: Origin::Filesystem,
+                mount: None,
+            },
+        }
+    }
+
+    /// Like [`Dir::from_path`], but verifies the directory up front. Returns an error of kind
+    /// `NotFound` if the path does not exist and `NotADirectory` if it is not a directory, so a
+    /// misconfigured asset directory is caught at startup rather than served as empty.
+    pub fn from_path_checked(path: &std::path::Path) -> std::io::Result<Self> {
+        let dir = Self::from_path(path);
+        let absolute = dir.absolute_path();
+        let metadata = std::fs::metadata(absolute)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {e}", absolute.display())))?;
+        if !metadata.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotADirectory,
+                format!("not a directory: {}", absolute.display()),
+            ));
+        }
+        Ok(dir)
+    }
+
+    /// Returns a dynamic directory at `rel` resolved against the directory containing the
+    /// running executable, so a binary shipped next to an `assets/` folder finds it regardless
+    /// of the working directory it was started from.
+    ///
+    /// The executable path from [`std::env::current_exe`] is canonicalized first, so a binary
+    /// started through a symlink resolves `rel` next to the real file, not the link. The
+    /// directory itself is not checked; pass the result's [`Dir::absolute_path`] to
+    /// [`Dir::from_path_checked`] to fail fast. Returns an error if the executable path is unknown.
+    pub fn from_exe_relative(rel: &str) -> std::io::Result<Dir> {
+        let exe = std::env::current_exe()?.canonicalize()?;
+        let exe_dir = exe.parent().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("executable has no parent directory: {}", exe.display()),
+            )
+        })?;
+        let path: Arc<std::path::Path> = Arc::from(exe_dir.join(rel));
+        Ok(Dir {
+            inner: InnerDir::Path {
+                root: path.clone(),
+                path,
+                origin: Origin::Filesystem,
+                mount: None,
             },
+        })
+    }
+
+    /// Returns a dynamic directory rooted at the path in the environment variable `env_key` if it
+    /// is set and names an existing directory, and `embedded` otherwise. Unlike
+    /// [`Dir::from_path`], the path is used as given: absolute, or relative to the working directory.
+    pub fn from_env_or_embedded(env_key: &str, embedded: Dir) -> Dir {
+        match std::env::var_os(env_key) {
+            Some(path) if std::path::Path::new(&path).is_dir() => {
+                let path: Arc<std::path::Path> = Arc::from(std::path::Path::new(&path));
+                Dir {
+                    inner: InnerDir::Path {
+                        root: path.clone(),
+                        path,
+                        origin: Origin::Filesystem,
+                        mount: None,
+                    },
+                }
+            }
+            _ => embedded,
         }
     }
 
     /// Converts an embedded directory to a dynamic (filesystem-backed) directory if possible.
     /// For embedded directories, this will create a Path variant using the embedded root path.
+    /// A `mount` prefix given to `fs_embed!` is kept, so both modes resolve the same paths.
     pub fn into_dynamic(self) -> Self {
         Self {
             inner: self.inner.into_dynamic(),
         }
     }
 
+    /// Wraps this directory in a single-layer [`DirSet`], for code written against sets.
+    pub fn into_dir_set(self) -> DirSet {
+        DirSet::new(vec![self])
+    }
+
     /// Automatically converts to a dynamic directory if in debug mode (cfg!(debug_assertions)).
     /// In release mode, returns self unchanged.
     pub fn auto_dynamic(self) -> Self {
         if cfg!(debug_assertions) {
-            return self.into_dynamic();
+            self.into_dynamic()
         } else {
-            return self;
+            self
         }
     }
 
     /// Creates a new root directory from the given string path, relative to the manifest directory.
     /// The path must be a string literal or static string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(path: &'static str) -> Self {
         Self::from_path(std::path::Path::new(path))
     }
@@ -204,29 +555,70 @@ impl Dir {
         self.inner.is_embedded()
     }
 
+    /// Returns where this directory originally came from, even after [`Dir::into_dynamic`].
+    pub fn origin(&self) -> Origin {
+        self.inner.origin()
+    }
+
     /// Returns the relative path of this directory.
     pub fn path(&self) -> &std::path::Path {
         self.inner.path()
     }
 
+    /// Returns the directory name (the last path component) as a string slice, if available.
+    /// The root of a tree has no name.
+    pub fn name(&self) -> Option<&str> {
+        self.path().file_name().and_then(|name| name.to_str())
+    }
+
     /// Returns the absolute path of this directory.
     pub fn absolute_path(&self) -> &std::path::Path {
         self.inner.absolute_path()
     }
 
+    /// Returns the directory's location with symlinks resolved, so two dynamic directories
+    /// reached through different links can be recognized as one. Dynamic directories call
+    /// [`std::fs::canonicalize`], which performs IO and fails if the directory does not exist.
+    /// Embedded directories return their relative path unchanged.
+    pub fn canonical_path(&self) -> std::io::Result<PathBuf> {
+        if self.is_embedded() {
+            Ok(self.path().to_path_buf())
+        } else {
+            self.absolute_path().canonicalize()
+        }
+    }
+
+    /// Returns true if both directories are backed by the same root and location,
+    /// unlike `==`, which only compares relative paths. Views made with [`Dir::with_file`],
+    /// [`Dir::filter`] or [`Dir::with_disk_overlay`] only match clones of the same view.
+    pub fn same_root(&self, other: &Dir) -> bool {
+        self.path() == other.path() && self.inner.same_layer(&other.inner)
+    }
+
     /// Returns all immediate entries (files and subdirectories) in this directory.
     pub fn entries(&self) -> Vec<DirEntry> {
         match &self.inner {
             InnerDir::Embed(dir, root) => dir
                 .files()
                 .map(|file| DirEntry {
-                    inner: InnerEntry::File(InnerFile::Embed(file.clone())),
+                    inner: InnerEntry::File(InnerFile::Embed(file, dir.clone(), *root)),
                 })
                 .chain(dir.dirs().map(|subdir| DirEntry {
-                    inner: InnerEntry::Dir(InnerDir::Embed(subdir.clone(), root)),
+                    inner: InnerEntry::Dir(InnerDir::Embed(subdir.clone(), *root)),
                 }))
                 .collect(),
-            InnerDir::Path { root, path } => {
+            InnerDir::Path {
+                root,
+                path,
+                origin,
+                mount: Some(mount),
+            } => mount::entries(root, path, *origin, mount),
+            InnerDir::Path {
+                root,
+                path,
+                origin,
+                mount: None,
+            } => {
                 let mut entries = Vec::new();
                 if let Ok(entries_iter) = std::fs::read_dir(path) {
                     for entry in entries_iter.flatten() {
@@ -236,13 +628,17 @@ impl Dir {
                                 inner: InnerEntry::File(InnerFile::Path {
                                     root: root.clone(),
                                     path: entry_path,
+                                    origin: *origin,
+                                    mounted: None,
                                 }),
                             });
                         } else if entry_path.is_dir() {
                             entries.push(DirEntry {
                                 inner: InnerEntry::Dir(InnerDir::Path {
                                     root: root.clone(),
-                                    path: entry_path,
+                                    path: Arc::from(entry_path),
+                                    origin: *origin,
+                                    mount: None,
                                 }),
                             });
                         }
@@ -250,68 +646,829 @@ impl Dir {
                 }
                 entries
             }
+            InnerDir::Overlay { base, files } => overlay::entries(base, files),
+            InnerDir::Filter {
+                base,
+                predicate,
+                visible,
+            } => filter::entries(base, predicate, visible.as_ref()),
+            InnerDir::DiskOverlay { base, disk_root } => disk_overlay::entries(base, disk_root),
         }
     }
 
     /// Returns the file with the given name if it exists in this directory.
-    /// The name is relative to the directory root.
+    /// The name is relative to this directory: empty and `.` segments (and so leading `/`s)
+    /// are ignored and `..` steps up a level, but a name that would leave this directory
+    /// returns `None`. Directory paths return `None` in both embedded and dynamic mode.
     pub fn get_file(&self, name: &str) -> Option<File> {
+        let name = lookup_name(name)?;
+        let name = name.as_str();
         match &self.inner {
-            InnerDir::Embed(dir, _) => dir.get_file(dir.path().join(name)).map(|file| File {
-                inner: InnerFile::Embed(file.clone()),
+            InnerDir::Embed(dir, root) => dir.get_file(dir.path().join(name)).map(|file| File {
+                inner: InnerFile::Embed(file, dir.clone(), *root),
             }),
-            InnerDir::Path { root, path } => {
+            InnerDir::Path {
+                root,
+                origin,
+                mount: Some(mount),
+                ..
+            } => mount::get_file(root, *origin, mount, name),
+            InnerDir::Path {
+                root,
+                path,
+                origin,
+                mount: None,
+            } => {
                 let new_path = path.join(name);
                 if new_path.is_file() {
                     Some(File {
                         inner: InnerFile::Path {
                             root: root.clone(),
                             path: new_path,
+                            origin: *origin,
+                            mounted: None,
                         },
                     })
                 } else {
                     None
                 }
             }
+            InnerDir::Overlay { base, files } => overlay::get_file(base, files, name),
+            InnerDir::Filter {
+                base, predicate, ..
+            } => filter::get_file(base, predicate, name),
+            InnerDir::DiskOverlay { base, disk_root } => {
+                disk_overlay::get_file(base, disk_root, name)
+            }
         }
     }
 
+    /// Reads the file with the given name, as [`Dir::get_file`] followed by [`File::read_bytes`].
+    /// Returns an error of kind `InvalidInput` if `name` is a directory, and `NotFound` if it
+    /// does not exist.
+    pub fn read_bytes(&self, name: &str) -> std::io::Result<Vec<u8>> {
+        if let Some(file) = self.get_file(name) {
+            return file.read_bytes();
+        }
+        let (kind, reason) = match self.get_dir(name) {
+            Some(_) => (std::io::ErrorKind::InvalidInput, "is a directory"),
+            None => (std::io::ErrorKind::NotFound, "file not found"),
+        };
+        Err(std::io::Error::new(kind, format!("{reason}: {name}")))
+    }
+
+    /// Like [`Dir::get_file`], but percent-decodes `url_path` first, so a request path such as
+    /// `/img/my%20logo.png` can be passed straight from a router. Decoded `..` segments are
+    /// confined to this directory as in [`Dir::get_file`].
+    /// Returns `None` if the decoded path is not valid UTF-8.
+    pub fn get_file_url(&self, url_path: &str) -> Option<File> {
+        let decoded = percent_encoding::percent_decode_str(url_path)
+            .decode_utf8()
+            .ok()?;
+        self.get_file(&decoded)
+    }
+
     /// Returns a reference to the directory with the given name, if it exists.
+    /// Names are normalized as in [`Dir::get_file`], so `"sub"`, `"sub/"` and `"/sub/"` match
+    /// alike and a name that would leave this directory returns `None`.
     pub fn get_dir(&self, name: &str) -> Option<Dir> {
+        let name = lookup_name(name)?;
+        let name = name.as_str();
         match &self.inner {
             InnerDir::Embed(dir, root) => dir.get_dir(dir.path().join(name)).map(|subdir| Dir {
-                inner: InnerDir::Embed(subdir.clone(), root),
+                inner: InnerDir::Embed(subdir.clone(), *root),
             }),
-            InnerDir::Path { root, path } => {
+            InnerDir::Path {
+                root,
+                origin,
+                mount: Some(mount),
+                ..
+            } => mount::get_dir(root, *origin, mount, name),
+            InnerDir::Path {
+                root,
+                path,
+                origin,
+                mount: None,
+            } => {
                 let new_path = path.join(name);
                 if new_path.is_dir() {
                     Some(Dir {
                         inner: InnerDir::Path {
                             root: root.clone(),
-                            path: new_path,
+                            path: Arc::from(new_path),
+                            origin: *origin,
+                            mount: None,
                         },
                     })
                 } else {
                     None
                 }
             }
+            InnerDir::Overlay { base, files } => overlay::get_dir(base, files, name),
+            InnerDir::Filter {
+                base,
+                predicate,
+                visible,
+            } => filter::get_dir(base, predicate, visible.as_ref(), name),
+            InnerDir::DiskOverlay { base, disk_root } => {
+                disk_overlay::get_dir(base, disk_root, name)
+            }
         }
     }
 
     /// Recursively walks all files in this directory and its subdirectories.
     /// Returns an iterator over all files found.
-    pub fn walk(&self) -> impl Iterator<Item = File> {
-        let mut queue: VecDeque<DirEntry> = VecDeque::from_iter(self.entries().into_iter());
+    ///
+    /// For embedded directories the file count is known upfront, so the iterator reports an
+    /// exact `size_hint` and `collect` allocates once.
+    ///
+    /// Directories nested deeper than [`DEFAULT_WALK_DEPTH`] levels are skipped, which guards
+    /// against symlink cycles and pathological trees in dynamic mode. Use
+    /// [`Dir::walk_max_depth`] to raise the limit or [`Dir::try_walk`] to detect truncation.
+    pub fn walk(&self) -> impl Iterator<Item = File> + Send {
+        self.walk_max_depth(DEFAULT_WALK_DEPTH)
+    }
+
+    /// Like [`Dir::walk`], but only descends `max_depth` levels: `1` yields the files directly
+    /// in this directory, `2` also those in its immediate subdirectories, and so on.
+    /// The exact `size_hint` of embedded walks only counts files within the limit.
+    pub fn walk_max_depth(&self, max_depth: usize) -> impl Iterator<Item = File> + Send {
+        Walk::new(self, max_depth)
+    }
+
+    /// Like [`Dir::walk_max_depth`], but yields an error for each directory that was skipped
+    /// for being too deep, so callers can tell a truncated walk from a complete one.
+    pub fn try_walk(&self, max_depth: usize) -> impl Iterator<Item = std::io::Result<File>> + Send {
+        let mut walk = Walk::new(self, max_depth);
+        std::iter::from_fn(move || {
+            walk.next_or_truncated().map(|next| {
+                next.map_err(|path| {
+                    std::io::Error::other(format!(
+                        "maximum walk depth {max_depth} exceeded at {}",
+                        path.display()
+                    ))
+                })
+            })
+        })
+    }
+
+    /// Recursively walks all files in a deterministic order: depth-first, with the entries of
+    /// each directory sorted by name. The order is identical for embedded and dynamic
+    /// directories, unlike [`Dir::walk`], which follows the backend's listing order.
+    /// Stops [`DEFAULT_WALK_DEPTH`] levels down, like [`Dir::walk`].
+    pub fn walk_sorted(&self) -> impl Iterator<Item = File> + Send {
+        let mut stack = self.sorted_entries(0);
+        stack.reverse();
+        std::iter::from_fn(move || {
+            while let Some((entry, depth)) = stack.pop() {
+                match entry.inner {
+                    InnerEntry::File(file) => return Some(File { inner: file }),
+                    InnerEntry::Dir(dir) => {
+                        stack.extend(Dir { inner: dir }.sorted_entries(depth).into_iter().rev())
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    fn sorted_entries(&self, depth: usize) -> Vec<(DirEntry, usize)> {
+        let mut entries = self.child_entries(depth);
+        entries.sort_by(|a, b| a.0.path().cmp(b.0.path()));
+        entries
+    }
+
+    /// Lists this directory for a recursive walk that found it `depth` levels down, pairing each
+    /// entry with its own depth. Lists nothing at [`DEFAULT_WALK_DEPTH`], so walks that follow
+    /// symlinks stop at cycles.
+    fn child_entries(&self, depth: usize) -> Vec<(DirEntry, usize)> {
+        if depth >= DEFAULT_WALK_DEPTH {
+            return Vec::new();
+        }
+        self.entries()
+            .into_iter()
+            .map(|entry| (entry, depth + 1))
+            .collect()
+    }
+
+    /// Recursively walks the files whose path, relative to this directory, starts with `prefix`.
+    /// Subdirectories outside the prefix are never descended into, and the walk stops
+    /// [`DEFAULT_WALK_DEPTH`] levels down, like [`Dir::walk`].
+    pub fn walk_under(&self, prefix: &str) -> impl Iterator<Item = File> + Send {
+        let prefix = self.path().join(prefix.trim_start_matches('/'));
+        let mut queue = VecDeque::from(self.child_entries(0));
         std::iter::from_fn(move || {
-            while let Some(entry) = queue.pop_front() {
+            while let Some((entry, depth)) = queue.pop_front() {
+                match entry.inner {
+                    InnerEntry::File(file) => {
+                        if file.path().starts_with(&prefix) {
+                            return Some(File { inner: file });
+                        }
+                    }
+                    InnerEntry::Dir(dir) => {
+                        if dir.path().starts_with(&prefix) || prefix.starts_with(dir.path()) {
+                            queue.extend(Dir { inner: dir }.child_entries(depth));
+                        }
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Recursively walks all entries, yielding every subdirectory as well as every file,
+    /// so directories without any files are preserved (e.g. when mirroring a tree).
+    ///
+    /// `fs_embed!` keeps empty directories it finds at build time, but note that version
+    /// control usually does not track empty directories, so they may be missing from the
+    /// checkout the binary was built from.
+    ///
+    /// Directories [`DEFAULT_WALK_DEPTH`] levels down are yielded but not listed.
+    pub fn walk_entries_keep_empty(&self) -> impl Iterator<Item = DirEntry> + Send {
+        let mut queue = VecDeque::from(self.child_entries(0));
+        std::iter::from_fn(move || {
+            let (entry, depth) = queue.pop_front()?;
+            if let InnerEntry::Dir(dir) = &entry.inner {
+                queue.extend(Dir { inner: dir.clone() }.child_entries(depth));
+            }
+            Some(entry)
+        })
+    }
+
+    /// Recursively walks every subdirectory below this one, breadth-first, without yielding files.
+    /// Stops [`DEFAULT_WALK_DEPTH`] levels down, like [`Dir::walk`].
+    pub fn walk_dirs(&self) -> impl Iterator<Item = Dir> + Send {
+        self.walk_entries_keep_empty()
+            .filter_map(DirEntry::into_dir)
+    }
+
+    /// Recursively walks all files, descending into a subdirectory only if `should_descend`
+    /// returns true for it. Pruned directories are never listed, which saves IO in dynamic mode.
+    /// Stops [`DEFAULT_WALK_DEPTH`] levels down, like [`Dir::walk`].
+    pub fn walk_pruned(&self, should_descend: impl Fn(&Dir) -> bool) -> impl Iterator<Item = File> {
+        let mut queue = VecDeque::from(self.child_entries(0));
+        std::iter::from_fn(move || {
+            while let Some((entry, depth)) = queue.pop_front() {
                 match entry.inner {
                     InnerEntry::File(file) => return Some(File { inner: file }),
-                    InnerEntry::Dir(dir) => queue.extend(Dir { inner: dir }.entries()),
+                    InnerEntry::Dir(dir) => {
+                        let dir = Dir { inner: dir };
+                        if should_descend(&dir) {
+                            queue.extend(dir.child_entries(depth));
+                        }
+                    }
                 }
             }
             None
         })
     }
+
+    /// Recursively walks all files, skipping any whose path relative to this directory matches
+    /// one of the glob `patterns`. Directories are matched too (as `dir` and `dir/`), so
+    /// excluding `private/**` never descends into `private/`.
+    ///
+    /// Returns an error if any pattern is not a valid glob.
+    #[cfg(feature = "glob")]
+    pub fn walk_excluding(
+        &self,
+        patterns: &[&str],
+    ) -> Result<impl Iterator<Item = File> + Send, globset::Error> {
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(globset::Glob::new(pattern)?);
+        }
+        let excluded = builder.build()?;
+        let base = self.path().to_path_buf();
+        let rel =
+            move |path: &std::path::Path| normalize_path(path.strip_prefix(&base).unwrap_or(path));
+
+        let dir_excluded = excluded.clone();
+        let dir_rel = rel.clone();
+        Ok(self
+            .walk_pruned(move |dir| {
+                let rel = dir_rel(dir.path());
+                !dir_excluded.is_match(&rel) && !dir_excluded.is_match(format!("{rel}/"))
+            })
+            .filter(move |file| !excluded.is_match(rel(file.path()))))
+    }
+
+    /// Walks all files and groups them by the relative path of their parent directory.
+    /// Groups are yielded in sorted order, with the files in each group sorted by path;
+    /// directories without files of their own are skipped.
+    pub fn grouped_by_dir(&self) -> impl Iterator<Item = (PathBuf, Vec<File>)> + Send {
+        let mut groups: std::collections::BTreeMap<PathBuf, Vec<File>> = Default::default();
+        for file in self.walk() {
+            let parent = file.path().parent().unwrap_or(std::path::Path::new(""));
+            groups.entry(parent.to_path_buf()).or_default().push(file);
+        }
+        groups.into_iter().map(|(dir, mut files)| {
+            files.sort_by(|a, b| a.path().cmp(b.path()));
+            (dir, files)
+        })
+    }
+
+    /// Walks every file like [`Dir::walk`], yielding its relative path together with its
+    /// location on disk. The second element is `None` for embedded files and files added
+    /// with [`Dir::with_file`], which have no on-disk path.
+    pub fn walk_paths(&self) -> impl Iterator<Item = (PathBuf, Option<PathBuf>)> + Send {
+        self.walk().map(|file| {
+            let absolute = match &file.inner {
+                InnerFile::Path { path, .. } => Some(path.clone()),
+                InnerFile::Embed(..) | InnerFile::Memory { .. } => None,
+            };
+            (file.path().to_path_buf(), absolute)
+        })
+    }
+
+    /// Returns true if any file in this tree satisfies `predicate`. The walk stops at the first
+    /// match, so dynamic subdirectories after it are never listed.
+    pub fn any(&self, predicate: impl Fn(&File) -> bool) -> bool {
+        self.walk().any(|file| predicate(&file))
+    }
+
+    /// Returns true if every file in this tree satisfies `predicate` (and for an empty tree).
+    /// The walk stops at the first failure.
+    pub fn all(&self, predicate: impl Fn(&File) -> bool) -> bool {
+        self.walk().all(|file| predicate(&file))
+    }
+
+    /// Returns a hex SHA-256 fingerprint of the whole tree, covering every file's path (relative
+    /// to this directory) and contents. Files are hashed in sorted path order, so the same set of
+    /// files gives the same value whether it is embedded or read from disk.
+    pub fn tree_hash(&self) -> std::io::Result<String> {
+        tree_hash(self.walk().map(|file| {
+            let rel = file.path().strip_prefix(self.path()).unwrap_or(file.path());
+            (normalize_path(rel), file)
+        }))
+    }
+
+    /// Returns the relative path of every file in this tree as a `/`-separated string, sorted.
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.walk().map(|file| file.rel_url()).collect();
+        paths.sort();
+        paths
+    }
+
+    /// Recursively counts the subdirectories and files below this directory, returned as
+    /// `(dirs, files)`. Embedded trees are counted in place and dynamic trees are listed
+    /// without building a `Dir` or `File` per node. Like [`Dir::walk_entries_keep_empty`],
+    /// directories [`DEFAULT_WALK_DEPTH`] levels down are counted but not listed.
+    pub fn count_entries(&self) -> (usize, usize) {
+        match &self.inner {
+            InnerDir::Embed(dir, ..) => count_embedded(dir),
+            InnerDir::Path {
+                path, mount: None, ..
+            } => count_on_disk(path, 0),
+            InnerDir::Path { mount: Some(_), .. }
+            | InnerDir::Overlay { .. }
+            | InnerDir::Filter { .. }
+            | InnerDir::DiskOverlay { .. } => {
+                self.walk_entries_keep_empty()
+                    .fold((0, 0), |(dirs, files), entry| {
+                        if entry.is_dir() {
+                            (dirs + 1, files)
+                        } else {
+                            (dirs, files + 1)
+                        }
+                    })
+            }
+        }
+    }
+
+    /// Returns each immediate child with its total size in bytes, largest first (ties by path).
+    /// A subdirectory's size is the sum of every file below it. Embedded sizes come from the
+    /// content lengths and dynamic ones from the filesystem; unreadable files count as zero.
+    pub fn size_report(&self) -> Vec<(PathBuf, u64)> {
+        fn file_size(file: &File) -> u64 {
+            match &file.inner {
+                InnerFile::Embed(embedded, ..) => embedded.contents().len() as u64,
+                _ => file.metadata().map(|m| m.size).unwrap_or(0),
+            }
+        }
+        let mut report: Vec<(PathBuf, u64)> = self
+            .entries()
+            .into_iter()
+            .map(|entry| {
+                let path = entry.path().to_path_buf();
+                let size = match entry.inner {
+                    InnerEntry::File(file) => file_size(&File { inner: file }),
+                    InnerEntry::Dir(dir) => Dir { inner: dir }.walk().map(|f| file_size(&f)).sum(),
+                };
+                (path, size)
+            })
+            .collect();
+        report.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        report
+    }
+
+    /// Compares an embedded directory with the source directory it was built from and returns
+    /// the paths whose contents drifted: files edited or deleted on disk since the build, and
+    /// files added on disk that were never embedded. Contents are compared by SHA-256.
+    /// Returns an empty list for directories that are not embedded.
+    pub fn diff_embedded_vs_disk(&self) -> std::io::Result<Vec<PathBuf>> {
+        if !self.is_embedded() {
+            return Ok(Vec::new());
+        }
+        let disk = self.clone().into_dynamic();
+        let mut embedded = std::collections::HashSet::new();
+        let mut drifted = std::collections::BTreeSet::new();
+        for file in self.walk() {
+            let rel = file.path().strip_prefix(self.path()).unwrap_or(file.path());
+            let unchanged = match disk.get_file(&normalize_path(rel)) {
+                Some(on_disk) => on_disk.sha256()? == file.sha256()?,
+                None => false,
+            };
+            if !unchanged {
+                drifted.insert(file.path().to_path_buf());
+            }
+            embedded.insert(file.path().to_path_buf());
+        }
+        for file in disk.walk() {
+            if !embedded.contains(file.path()) {
+                drifted.insert(file.path().to_path_buf());
+            }
+        }
+        Ok(drifted.into_iter().collect())
+    }
+
+    /// Returns every relative path that occurs more than once in this directory tree,
+    /// after normalizing separators to `/`. Each duplicate is reported once, in sorted order.
+    pub fn duplicate_paths(&self) -> Vec<PathBuf> {
+        self.find_duplicates(|key| key)
+    }
+
+    /// Like [`Dir::duplicate_paths`], but also treats paths differing only in case as duplicates.
+    /// Useful for catching trees that will collide on case-insensitive filesystems.
+    pub fn duplicate_paths_ignore_case(&self) -> Vec<PathBuf> {
+        self.find_duplicates(|key| key.to_lowercase())
+    }
+
+    fn find_duplicates(&self, fold: impl Fn(String) -> String) -> Vec<PathBuf> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates = std::collections::BTreeSet::new();
+        for file in self.walk() {
+            let key = fold(normalize_path(file.path()));
+            if !seen.insert(key.clone()) {
+                duplicates.insert(key);
+            }
+        }
+        duplicates.into_iter().map(PathBuf::from).collect()
+    }
+
+    /// Returns up to `max` file paths, relative to this directory, that are closest to `name`
+    /// by edit distance. Intended for "did you mean" hints after a failed [`Dir::get_file`].
+    pub fn suggest(&self, name: &str, max: usize) -> Vec<String> {
+        let name = name.trim_start_matches('/');
+        let mut candidates: Vec<(usize, String)> = self
+            .walk()
+            .map(|file| {
+                let rel = file.path().strip_prefix(self.path()).unwrap_or(file.path());
+                let rel = normalize_path(rel);
+                (levenshtein(name, &rel), rel)
+            })
+            .collect();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(max)
+            .map(|(_, rel)| rel)
+            .collect()
+    }
+}
+
+/// Formats bytes as lowercase hexadecimal.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Computes the Levenshtein edit distance between two strings, by character.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Breadth-first iterator behind [`Dir::walk`], tracking how many files are left when known.
+struct Walk {
+    /// Pending entries with their depth below the walked directory, starting at 1.
+    queue: VecDeque<(DirEntry, usize)>,
+    remaining: Option<usize>,
+    max_depth: usize,
+}
+
+impl Walk {
+    fn new(dir: &Dir, max_depth: usize) -> Self {
+        let remaining = match &dir.inner {
+            InnerDir::Embed(dir, ..) => Some(count_embedded_files(dir, max_depth)),
+            InnerDir::Path { .. }
+            | InnerDir::Overlay { .. }
+            | InnerDir::Filter { .. }
+            | InnerDir::DiskOverlay { .. } => None,
+        };
+        let queue = match max_depth {
+            0 => VecDeque::new(),
+            _ => dir.entries().into_iter().map(|entry| (entry, 1)).collect(),
+        };
+        Walk {
+            queue,
+            remaining,
+            max_depth,
+        }
+    }
+
+    /// Returns the next file, or the path of a directory skipped for exceeding `max_depth`.
+    fn next_or_truncated(&mut self) -> Option<Result<File, PathBuf>> {
+        while let Some((entry, depth)) = self.queue.pop_front() {
+            match entry.inner {
+                InnerEntry::File(file) => {
+                    if let Some(remaining) = &mut self.remaining {
+                        *remaining = remaining.saturating_sub(1);
+                    }
+                    return Some(Ok(File { inner: file }));
+                }
+                InnerEntry::Dir(dir) if depth >= self.max_depth => {
+                    return Some(Err(dir.path().to_path_buf()));
+                }
+                InnerEntry::Dir(dir) => self.queue.extend(
+                    Dir { inner: dir }
+                        .entries()
+                        .into_iter()
+                        .map(|entry| (entry, depth + 1)),
+                ),
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Walk {
+    type Item = File;
+
+    fn next(&mut self) -> Option<File> {
+        loop {
+            if let Ok(file) = self.next_or_truncated()? {
+                return Some(file);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
+fn count_embedded(dir: &include_dir::Dir<'static>) -> (usize, usize) {
+    dir.entries()
+        .iter()
+        .fold((0, 0), |(dirs, files), entry| match entry {
+            include_dir::DirEntry::Dir(subdir) => {
+                let (d, f) = count_embedded(subdir);
+                (dirs + 1 + d, files + f)
+            }
+            include_dir::DirEntry::File(_) => (dirs, files + 1),
+        })
+}
+
+/// Counts the files a walk limited to `max_depth` levels yields from an embedded tree.
+fn count_embedded_files(dir: &include_dir::Dir<'static>, max_depth: usize) -> usize {
+    if max_depth == 0 {
+        return 0;
+    }
+    dir.entries()
+        .iter()
+        .map(|entry| match entry {
+            include_dir::DirEntry::Dir(subdir) => count_embedded_files(subdir, max_depth - 1),
+            include_dir::DirEntry::File(_) => 1,
+        })
+        .sum()
+}
+
+/// Counts the entries below `path`, which is `depth` levels below the counted directory.
+fn count_on_disk(path: &std::path::Path, depth: usize) -> (usize, usize) {
+    if depth >= DEFAULT_WALK_DEPTH {
+        return (0, 0);
+    }
+    let (mut dirs, mut files) = (0, 0);
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+    for entry in read_dir.flatten() {
+        let Ok(mut file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            // Follow links like `entries` does.
+            match std::fs::metadata(entry.path()) {
+                Ok(metadata) => file_type = metadata.file_type(),
+                Err(_) => continue,
+            }
+        }
+        if file_type.is_dir() {
+            let (d, f) = count_on_disk(&entry.path(), depth + 1);
+            dirs += 1 + d;
+            files += f;
+        } else if file_type.is_file() {
+            files += 1;
+        }
+    }
+    (dirs, files)
+}
+
+/// Returns the deepest relative directory that contains every file in `files`, compared
+/// component by component. Files at the top level share the empty path.
+/// Returns `None` if `files` is empty.
+pub fn common_ancestor(files: &[File]) -> Option<PathBuf> {
+    let (first, rest) = files.split_first()?;
+    let mut ancestor: Vec<_> = first
+        .path()
+        .parent()
+        .map(|parent| parent.components().collect())
+        .unwrap_or_default();
+    for file in rest {
+        let parent = file.path().parent().unwrap_or(std::path::Path::new(""));
+        let shared = ancestor
+            .iter()
+            .zip(parent.components())
+            .take_while(|(a, b)| *a == b)
+            .count();
+        ancestor.truncate(shared);
+    }
+    Some(ancestor.iter().collect())
+}
+
+/// Hashes `(path, file)` pairs in path order. Each file contributes its path, a NUL separator
+/// and the digest of its contents, so boundaries between files cannot be shifted.
+fn tree_hash(files: impl Iterator<Item = (String, File)>) -> std::io::Result<String> {
+    let mut files: Vec<(String, File)> = files.collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut hasher = sha2::Sha256::new();
+    for (path, file) in files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(file.sha256()?);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// UTF-8 validation results for embedded contents, keyed by the address and length of the bytes.
+type Utf8Cache = std::collections::HashMap<(usize, usize), Option<&'static str>>;
+
+/// Validates embedded `contents` as UTF-8, remembering the outcome so each file is checked once.
+fn cached_utf8(contents: &'static [u8]) -> Option<&'static str> {
+    static CACHE: std::sync::OnceLock<std::sync::RwLock<Utf8Cache>> = std::sync::OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let key = (contents.as_ptr() as usize, contents.len());
+    if let Some(cached) = cache.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return *cached;
+    }
+    let text = std::str::from_utf8(contents).ok();
+    cache
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key, text);
+    text
+}
+
+/// SHA-256 digests of embedded contents, keyed by the address and length of the bytes.
+type DigestCache = std::collections::HashMap<(usize, usize), [u8; 32]>;
+
+/// Hashes embedded `contents`, remembering the digest so each file is hashed once.
+fn cached_sha256(contents: &'static [u8]) -> [u8; 32] {
+    static CACHE: std::sync::OnceLock<std::sync::RwLock<DigestCache>> = std::sync::OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let key = (contents.as_ptr() as usize, contents.len());
+    if let Some(cached) = cache.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return *cached;
+    }
+    let digest: [u8; 32] = sha2::Sha256::digest(contents).into();
+    cache
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key, digest);
+    digest
+}
+
+/// Source locations handed out by [`File::source_location`], keyed by the macro path and the
+/// file's path below it.
+type SourceCache = std::collections::HashMap<(&'static str, String), &'static str>;
+
+/// Joins `source` and `rel` into a string that lives for the rest of the process, allocating
+/// it only once per file.
+fn cached_source_location(source: &'static str, rel: &str) -> &'static str {
+    static CACHE: std::sync::OnceLock<std::sync::RwLock<SourceCache>> = std::sync::OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let key = (source, rel.to_string());
+    if let Some(cached) = cache.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return cached;
+    }
+    let location = if source.is_empty() {
+        rel.to_string()
+    } else {
+        format!("{source}/{rel}")
+    };
+    cache
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(key)
+        .or_insert_with(|| Box::leak(location.into_boxed_str()))
+}
+
+/// The heuristic behind [`File::is_binary`].
+fn looks_binary(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return true;
+    }
+    let control = bytes
+        .iter()
+        .filter(|&&b| (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b)) || b == 0x7f)
+        .count();
+    control * 10 > bytes.len() * 3
+}
+
+/// Normalizes a name passed to [`Dir::get_file`] or [`Dir::get_dir`] into a `/`-separated path
+/// below the directory. Empty and `.` segments are dropped and `..` removes the previous
+/// segment. Returns `None` if the name would leave the directory or has a segment that is not
+/// a plain file name on this platform, such as a drive prefix, so it can be joined safely.
+fn lookup_name(name: &str) -> Option<String> {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in name.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => {
+                let mut components = std::path::Path::new(segment).components();
+                match (components.next(), components.next()) {
+                    (Some(std::path::Component::Normal(_)), None) => segments.push(segment),
+                    _ => return None,
+                }
+            }
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Joins the components of a relative path with `/`, regardless of platform.
+fn normalize_path(path: &std::path::Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Characters percent-encoded within a single URL path segment: everything except the
+/// RFC 3986 unreserved set and the sub-delimiters that are safe in paths.
+const URL_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=')
+    .remove(b':')
+    .remove(b'@');
+
+/// Wraps a directory produced by your own `include_dir!` call.
+///
+/// The root used by [`Dir::into_dynamic`] is taken from `dir.path()`, which is empty for the
+/// top level of an `include_dir!` tree; use [`Dir::from_embedded`] to supply the on-disk root
+/// explicitly when dynamic mode is needed.
+impl From<&'static include_dir::Dir<'static>> for Dir {
+    fn from(dir: &'static include_dir::Dir<'static>) -> Self {
+        Self::from_embedded(dir.clone(), dir.path().to_str().unwrap_or(""))
+    }
+}
+
+/// A lone directory is a single-layer set, so functions can accept `impl Into<DirSet>`.
+impl From<Dir> for DirSet {
+    fn from(dir: Dir) -> Self {
+        dir.into_dir_set()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -342,55 +1499,492 @@ impl File {
         self.inner.is_embedded()
     }
 
+    /// Returns where this file originally came from, even after [`Dir::into_dynamic`].
+    pub fn origin(&self) -> Origin {
+        self.inner.origin()
+    }
+
     /// Returns the relative path of this file.
     pub fn path(&self) -> &std::path::Path {
         self.inner.path()
     }
 
+    /// Returns this file as a fully owned `'static` handle, suitable for storing in caches or
+    /// generic containers. Every backend already owns or `'static`-borrows its data, so this
+    /// is free; it exists to make that guarantee part of the API.
+    pub fn into_owned(self) -> File {
+        self
+    }
+
+    /// Returns the relative path as a `/`-separated string, regardless of platform.
+    pub fn rel_url(&self) -> String {
+        normalize_path(self.path())
+    }
+
+    /// Like [`File::rel_url`], but percent-encodes each path segment so names with spaces,
+    /// `#`, `?` or non-ASCII characters form a valid URL path. The `/` separators are kept.
+    pub fn rel_url_encoded(&self) -> String {
+        self.rel_url()
+            .split('/')
+            .map(|segment| percent_encoding::utf8_percent_encode(segment, URL_SEGMENT).to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Returns the embedded contents without copying, or `None` for files read from disk
+    /// or added with [`Dir::with_file`].
+    pub fn as_static_bytes(&self) -> Option<&'static [u8]> {
+        match &self.inner {
+            InnerFile::Embed(file, ..) => Some(file.contents()),
+            InnerFile::Path { .. } | InnerFile::Memory { .. } => None,
+        }
+    }
+
+    /// Returns the crate-relative path of an embedded file's source, i.e. the path written in
+    /// the `fs_embed!` call joined with the file's relative path (without any `mount` prefix),
+    /// such as `"tests/data/alpha.txt"`, so error messages can point at the file to edit.
+    ///
+    /// Returns `None` for files read from disk, which already have [`File::absolute_path`], for
+    /// files added with [`Dir::with_file`], and for trees built with [`Dir::from_embedded`]
+    /// rather than the macro. The string is built on first use and kept for the life of the process.
+    pub fn source_location(&self) -> Option<&'static str> {
+        match &self.inner {
+            InnerFile::Embed(file, _, root) => {
+                let source = root.source?;
+                let rel = file.path().strip_prefix(root.mount).unwrap_or(file.path());
+                Some(cached_source_location(source, &normalize_path(rel)))
+            }
+            InnerFile::Path { .. } | InnerFile::Memory { .. } => None,
+        }
+    }
+
+    /// Returns the raw metadata recorded by `fs_embed!` at build time, for fields that
+    /// [`FileMetaData`] does not surface. Returns `None` for files read from disk or added with
+    /// [`Dir::with_file`], and for embedded files whose timestamps were unavailable at build time.
+    pub fn embedded_metadata(&self) -> Option<&'static include_dir::Metadata> {
+        match &self.inner {
+            InnerFile::Embed(file, ..) => file.metadata(),
+            InnerFile::Path { .. } | InnerFile::Memory { .. } => None,
+        }
+    }
+
+    /// Returns the embedded contents as a string slice without copying.
+    /// Returns `None` for files read from disk or if the contents are not valid UTF-8.
+    ///
+    /// UTF-8 validation runs on the first call for each embedded file and its outcome is cached
+    /// for the life of the process, so later calls cost a hash-map lookup instead of a pass
+    /// over the whole file.
+    pub fn as_static_str(&self) -> Option<&'static str> {
+        self.as_static_bytes().and_then(cached_utf8)
+    }
+
     /// Reads the file contents as bytes.
     pub fn read_bytes(&self) -> std::io::Result<Vec<u8>> {
         match &self.inner {
-            InnerFile::Embed(file) => Ok(file.contents().to_vec()),
-            InnerFile::Path { path, .. } => std::fs::read(path),
+            InnerFile::Embed(file, ..) => Ok(file.contents().to_vec()),
+            InnerFile::Memory { file, .. } => Ok(file.contents.clone()),
+            InnerFile::Path { path, .. } => std::fs::read(path).map_err(|e| self.path_error(e)),
         }
     }
 
     /// Reads the file contents as a UTF-8 string.
     /// Returns an error if the contents are not valid UTF-8.
+    ///
+    /// Embedded files are validated once per process (see [`File::as_static_str`]), so repeated
+    /// reads only pay for the copy into the returned `String`.
     pub fn read_str(&self) -> std::io::Result<String> {
+        let contents = match &self.inner {
+            InnerFile::Embed(file, ..) => match cached_utf8(file.contents()) {
+                Some(text) => return Ok(text.to_owned()),
+                None => file.contents(),
+            },
+            InnerFile::Memory { file, .. } => &file.contents,
+            InnerFile::Path { path, .. } => {
+                return std::fs::read_to_string(path).map_err(|e| self.path_error(e));
+            }
+        };
+        std::str::from_utf8(contents)
+            .map(str::to_owned)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Reads the file contents as a UTF-8 string and passes them through `transform`, e.g. to
+    /// substitute `{{version}}` placeholders before serving.
+    pub fn read_str_with(&self, transform: impl Fn(&str) -> String) -> std::io::Result<String> {
+        Ok(transform(&self.read_str()?))
+    }
+
+    /// Reads the file contents as a UTF-8 string with every `\r\n` converted to `\n`.
+    /// Use [`File::read_str`] when the contents must be byte-faithful.
+    pub fn read_str_normalized(&self) -> std::io::Result<String> {
+        let text = self.read_str()?;
+        if text.contains("\r\n") {
+            Ok(text.replace("\r\n", "\n"))
+        } else {
+            Ok(text)
+        }
+    }
+
+    /// Returns an iterator over the file contents in pieces of `chunk_size` bytes; the final
+    /// chunk may be shorter. Embedded files are sliced from memory and dynamic files are read
+    /// incrementally, so memory use stays bounded. Iteration stops after the first error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks(
+        &self,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = std::io::Result<Vec<u8>>> + Send {
+        use std::io::Read;
+        assert!(chunk_size > 0, "chunk_size must be non-zero");
+        let file = self.clone();
+        let mut offset = 0;
+        let mut reader: Option<std::fs::File> = None;
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let mut slice = |contents: &[u8]| {
+                let end = contents.len().min(offset + chunk_size);
+                let chunk = contents[offset..end].to_vec();
+                offset = end;
+                chunk
+            };
+            let chunk = match &file.inner {
+                InnerFile::Embed(embedded, ..) => Ok(slice(embedded.contents())),
+                InnerFile::Memory { file: memory, .. } => Ok(slice(&memory.contents)),
+                InnerFile::Path { path, .. } => {
+                    let mut chunk = Vec::with_capacity(chunk_size);
+                    let reader = match reader.as_mut() {
+                        Some(reader) => Ok(reader),
+                        None => std::fs::File::open(path).map(|opened| reader.insert(opened)),
+                    };
+                    reader
+                        .and_then(|reader| reader.take(chunk_size as u64).read_to_end(&mut chunk))
+                        .map(|_| chunk)
+                        .map_err(|e| file.path_error(e))
+                }
+            };
+            match chunk {
+                Ok(chunk) if chunk.is_empty() => {
+                    done = true;
+                    None
+                }
+                Ok(chunk) => Some(Ok(chunk)),
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Reads up to the first `n` bytes of the file, e.g. for magic-number sniffing.
+    /// Embedded data is sliced and dynamic files are read no further than `n` bytes.
+    pub fn read_prefix(&self, n: usize) -> std::io::Result<Vec<u8>> {
+        match &self.inner {
+            InnerFile::Embed(file, ..) => {
+                let contents = file.contents();
+                Ok(contents[..contents.len().min(n)].to_vec())
+            }
+            InnerFile::Memory { file, .. } => {
+                Ok(file.contents[..file.contents.len().min(n)].to_vec())
+            }
+            InnerFile::Path { path, .. } => {
+                use std::io::Read;
+                let mut bytes = Vec::new();
+                std::fs::File::open(path)
+                    .and_then(|file| file.take(n as u64).read_to_end(&mut bytes))
+                    .map_err(|e| self.path_error(e))?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Guesses whether the file is binary from its first 8 KiB: true if that prefix contains a
+    /// NUL byte or more than 30% control characters (other than whitespace and escape).
+    /// Embedded files are inspected in place; dynamic files read no further than the prefix.
+    /// An empty file counts as text.
+    pub fn is_binary(&self) -> std::io::Result<bool> {
+        const PREFIX: usize = 8192;
+        Ok(match &self.inner {
+            InnerFile::Embed(file, ..) => {
+                let contents = file.contents();
+                looks_binary(&contents[..contents.len().min(PREFIX)])
+            }
+            InnerFile::Path { .. } | InnerFile::Memory { .. } => {
+                looks_binary(&self.read_prefix(PREFIX)?)
+            }
+        })
+    }
+
+    /// Reads at most `max_bytes` of the file as a UTF-8 string, for previews of large files.
+    /// If the limit splits a multi-byte character, the partial character is dropped instead of
+    /// producing an error. Contents that are invalid UTF-8 before the limit still return an error.
+    pub fn read_str_prefix(&self, max_bytes: usize) -> std::io::Result<String> {
+        match String::from_utf8(self.read_prefix(max_bytes)?) {
+            Ok(text) => Ok(text),
+            Err(e) if e.utf8_error().error_len().is_none() => {
+                let valid = e.utf8_error().valid_up_to();
+                let mut bytes = e.into_bytes();
+                bytes.truncate(valid);
+                Ok(String::from_utf8(bytes).expect("truncated to a valid UTF-8 boundary"))
+            }
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        }
+    }
+
+    /// Returns a weak RFC 7232 ETag (`W/"<size>-<mtime>"`) derived from the file's size and
+    /// modification time. It is cheap to compute, which suits dynamic mode during development,
+    /// but an edit that keeps both size and mtime (to the second) unchanged goes unnoticed.
+    /// Use [`File::etag`] when responses must change whenever the content does. Embedded files
+    /// built without timestamps use an mtime of zero, so their weak ETag reflects only the size.
+    pub fn weak_etag(&self) -> std::io::Result<String> {
+        let metadata = self.metadata_or_epoch()?;
+        let modified = metadata
+            .modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Ok(format!("W/\"{:x}-{:x}\"", metadata.size, modified))
+    }
+
+    /// Returns a strong ETag (`"<sha256>"`) computed from the file's contents.
+    /// Embedded files are hashed once per process; other files are read and hashed on every call.
+    pub fn etag(&self) -> std::io::Result<String> {
+        Ok(format!("\"{}\"", to_hex(&self.sha256()?)))
+    }
+
+    /// Returns the SHA-256 digest of the file's contents.
+    fn sha256(&self) -> std::io::Result<[u8; 32]> {
+        if let InnerFile::Embed(file, ..) = &self.inner {
+            return Ok(cached_sha256(file.contents()));
+        }
+        let mut hasher = sha2::Sha256::new();
+        self.hash_into(&mut hasher)?;
+        Ok(hasher.finalize().into())
+    }
+
+    /// Feeds the file's contents into `hasher`, streaming from disk for dynamic files.
+    fn hash_into(&self, hasher: &mut sha2::Sha256) -> std::io::Result<()> {
         match &self.inner {
-            InnerFile::Embed(file) => std::str::from_utf8(file.contents())
-                .map(str::to_owned)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
-            InnerFile::Path { path, .. } => std::fs::read_to_string(path),
+            InnerFile::Embed(file, ..) => {
+                hasher.update(file.contents());
+                Ok(())
+            }
+            InnerFile::Memory { file, .. } => {
+                hasher.update(&file.contents);
+                Ok(())
+            }
+            InnerFile::Path { path, .. } => {
+                std::fs::File::open(path)
+                    .and_then(|mut file| std::io::copy(&mut file, hasher))
+                    .map_err(|e| self.path_error(e))?;
+                Ok(())
+            }
         }
     }
 
     /// Returns the metadata for this file, such as modification time and size.
+    /// Fails for embedded files built with `fs_embed!(.., metadata = false)`, which record no
+    /// modification time.
     pub fn metadata(&self) -> std::io::Result<FileMetaData> {
         match &self.inner {
-            InnerFile::Embed(file) => {
+            InnerFile::Embed(file, ..) => {
                 if let Some(metadata) = file.metadata() {
                     Ok(FileMetaData {
                         modified: metadata.modified(),
                         size: file.contents().len() as u64,
                     })
                 } else {
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
+                    Err(std::io::Error::other(
                         "Failed to get embedded file metadata",
                     ))
                 }
             }
+            InnerFile::Memory { file, .. } => Ok(FileMetaData {
+                modified: file.modified,
+                size: file.contents.len() as u64,
+            }),
             InnerFile::Path { path, .. } => {
-                let metadata = std::fs::metadata(path)?;
+                let metadata = std::fs::metadata(path).map_err(|e| self.path_error(e))?;
                 Ok(FileMetaData {
-                    modified: metadata.modified()?,
+                    modified: metadata.modified().map_err(|e| self.path_error(e))?,
                     size: metadata.len(),
                 })
             }
         }
     }
+
+    /// Like [`File::metadata`], but an embedded file built without timestamps reports the Unix
+    /// epoch and its content length instead of failing. Every API that needs a modification
+    /// time goes through this, so `fs_embed!(.., metadata = false)` trees keep working.
+    pub(crate) fn metadata_or_epoch(&self) -> std::io::Result<FileMetaData> {
+        match &self.inner {
+            InnerFile::Embed(file, ..) if file.metadata().is_none() => Ok(FileMetaData {
+                modified: std::time::UNIX_EPOCH,
+                size: file.contents().len() as u64,
+            }),
+            _ => self.metadata(),
+        }
+    }
+
+    /// Returns where this file points if it is a symbolic link on disk, or `None` for regular,
+    /// embedded and in-memory files. Other methods follow links transparently.
+    pub fn symlink_target(&self) -> std::io::Result<Option<PathBuf>> {
+        match &self.inner {
+            InnerFile::Embed(..) | InnerFile::Memory { .. } => Ok(None),
+            InnerFile::Path { path, .. } => {
+                let metadata = std::fs::symlink_metadata(path).map_err(|e| self.path_error(e))?;
+                if metadata.file_type().is_symlink() {
+                    std::fs::read_link(path)
+                        .map(Some)
+                        .map_err(|e| self.path_error(e))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Resolves `rel` against this file's parent directory and returns the target from `owner`,
+    /// as needed for includes and imports referenced from within a file. `.` and `..` segments
+    /// are normalized, and a path starting with `/` resolves from `owner`'s root.
+    /// Returns `None` if the target does not exist or would escape `owner`.
+    pub fn resolve_relative(&self, owner: &Dir, rel: &str) -> Option<File> {
+        let mut segments: Vec<String> = if rel.starts_with('/') {
+            Vec::new()
+        } else {
+            let parent = self.path().parent().unwrap_or(std::path::Path::new(""));
+            let parent = normalize_path(parent);
+            let owner_key = normalize_path(owner.path());
+            let inside = if owner_key.is_empty() {
+                parent.clone()
+            } else {
+                match parent.strip_prefix(&owner_key) {
+                    Some("") => String::new(),
+                    Some(rest) => rest.strip_prefix('/')?.to_string(),
+                    None => return None,
+                }
+            };
+            inside
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        for segment in rel.split(['/', '\\']) {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop()?;
+                }
+                name => segments.push(name.to_string()),
+            }
+        }
+        owner.get_file(&segments.join("/"))
+    }
+
+    /// Returns the relative path from this file's directory to `target`, inserting `..` as
+    /// needed, for links between generated pages: from `a/b/page.html` to `a/c/other.html`
+    /// this is `../c/other.html`. Only the relative paths are compared; nothing is read.
+    pub fn link_to(&self, target: &File) -> PathBuf {
+        let from: Vec<_> = self
+            .path()
+            .parent()
+            .map(|parent| parent.components().collect())
+            .unwrap_or_default();
+        let to: Vec<_> = target.path().components().collect();
+        let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+        std::iter::repeat_n(std::path::Component::ParentDir, from.len() - common)
+            .chain(to[common..].iter().copied())
+            .collect()
+    }
+
+    /// Returns the other files in this file's directory, sorted by name. Embedded files are
+    /// looked up in the embedded tree and dynamic files list their parent directory on disk.
+    /// Files added with [`Dir::with_file`] do not know their directory and return an error of
+    /// kind `Unsupported`.
+    pub fn siblings(&self) -> std::io::Result<Vec<File>> {
+        let mut siblings: Vec<File> = match &self.inner {
+            InnerFile::Embed(file, ancestor, root) => {
+                let parent_path = file.path().parent().unwrap_or(std::path::Path::new(""));
+                let parent = if parent_path == ancestor.path() {
+                    Some(ancestor.clone())
+                } else {
+                    ancestor.get_dir(parent_path).cloned()
+                };
+                match parent {
+                    Some(parent) => parent
+                        .files()
+                        .map(|sibling| File {
+                            inner: InnerFile::Embed(sibling, parent.clone(), *root),
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                }
+            }
+            InnerFile::Path {
+                root,
+                path,
+                origin,
+                mounted,
+            } => {
+                let Some(parent) = path.parent() else {
+                    return Ok(Vec::new());
+                };
+                let mut files = Vec::new();
+                for entry in std::fs::read_dir(parent).map_err(|e| self.path_error(e))? {
+                    let entry_path = entry.map_err(|e| self.path_error(e))?.path();
+                    if entry_path.is_file() {
+                        let mounted =
+                            mounted
+                                .as_ref()
+                                .zip(entry_path.file_name())
+                                .map(|(rel, name)| {
+                                    rel.parent().unwrap_or(std::path::Path::new("")).join(name)
+                                });
+                        files.push(File {
+                            inner: InnerFile::Path {
+                                root: root.clone(),
+                                path: entry_path,
+                                origin: *origin,
+                                mounted,
+                            },
+                        });
+                    }
+                }
+                files
+            }
+            InnerFile::Memory { .. } => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    format!(
+                        "{}: in-memory files have no siblings",
+                        self.path().display()
+                    ),
+                ));
+            }
+        };
+        siblings.retain(|sibling| sibling.path() != self.path());
+        siblings.sort_by(|a, b| a.path().file_name().cmp(&b.path().file_name()));
+        Ok(siblings)
+    }
+
+    /// Wraps a filesystem error with this file's paths, keeping its kind.
+    fn path_error(&self, source: std::io::Error) -> std::io::Error {
+        let kind = source.kind();
+        std::io::Error::new(
+            kind,
+            PathError {
+                relative: self.path().to_path_buf(),
+                absolute: self.absolute_path().to_path_buf(),
+                source,
+            },
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -422,6 +2016,11 @@ impl DirEntry {
         }
     }
 
+    /// Returns the entry name (the last path component) as a string slice, if available.
+    pub fn name(&self) -> Option<&str> {
+        self.path().file_name().and_then(|name| name.to_str())
+    }
+
     /// Returns the absolute path of this entry.
     pub fn absolute_path(&self) -> &std::path::Path {
         match &self.inner {
@@ -432,8 +2031,18 @@ impl DirEntry {
 
     /// Returns true if this entry is embedded in the binary.
     pub fn is_embedded(&self) -> bool {
-        matches!(&self.inner, InnerEntry::File(InnerFile::Embed(_)))
-            || matches!(&self.inner, InnerEntry::Dir(InnerDir::Embed(..)))
+        match &self.inner {
+            InnerEntry::File(file) => file.is_embedded(),
+            InnerEntry::Dir(dir) => dir.is_embedded(),
+        }
+    }
+
+    /// Returns where this entry originally came from, even after [`Dir::into_dynamic`].
+    pub fn origin(&self) -> Origin {
+        match &self.inner {
+            InnerEntry::File(file) => file.origin(),
+            InnerEntry::Dir(dir) => dir.origin(),
+        }
     }
 
     /// Returns true if this entry is a file.
@@ -446,6 +2055,16 @@ impl DirEntry {
         matches!(&self.inner, InnerEntry::Dir(_))
     }
 
+    /// Lists the immediate children of a directory entry, like [`Dir::entries`], or returns
+    /// `None` for a file. Nothing below the children is read, so a tree view can load one
+    /// level each time a folder is expanded.
+    pub fn expand(&self) -> Option<Vec<DirEntry>> {
+        match &self.inner {
+            InnerEntry::Dir(dir) => Some(Dir { inner: dir.clone() }.entries()),
+            InnerEntry::File(_) => None,
+        }
+    }
+
     /// Converts this entry into a file, if it is a file.
     pub fn into_file(self) -> Option<File> {
         if let InnerEntry::File(file) = self.inner {
@@ -480,6 +2099,51 @@ impl DirSet {
         Self { dirs }
     }
 
+    /// Converts every layer with [`Dir::into_dynamic`].
+    pub fn into_dynamic(self) -> Self {
+        Self {
+            dirs: self.dirs.into_iter().map(Dir::into_dynamic).collect(),
+        }
+    }
+
+    /// Applies [`Dir::auto_dynamic`] to every layer: filesystem-backed in debug mode,
+    /// unchanged in release mode.
+    pub fn auto_dynamic(self) -> Self {
+        Self {
+            dirs: self.dirs.into_iter().map(Dir::auto_dynamic).collect(),
+        }
+    }
+
+    /// Removes layers backed by the same root as a later layer (see [`Dir::same_root`]).
+    /// The highest-precedence occurrence is kept, so lookups resolve exactly as before.
+    ///
+    /// Dynamic layers with the same relative path are also compared by [`Dir::canonical_path`],
+    /// so the same directory reached through a symlink collapses too. That comparison touches
+    /// the filesystem.
+    pub fn dedup_roots(&mut self) {
+        fn same_target(a: &Dir, b: &Dir) -> bool {
+            matches!(
+                (&a.inner, &b.inner),
+                (InnerDir::Path { .. }, InnerDir::Path { .. })
+            ) && a.path() == b.path()
+                && matches!(
+                    (a.canonical_path(), b.canonical_path()),
+                    (Ok(a), Ok(b)) if a == b
+                )
+        }
+        let mut kept: Vec<Dir> = Vec::with_capacity(self.dirs.len());
+        for dir in self.dirs.drain(..).rev() {
+            if !kept
+                .iter()
+                .any(|k| k.same_root(&dir) || same_target(k, &dir))
+            {
+                kept.push(dir);
+            }
+        }
+        kept.reverse();
+        self.dirs = kept;
+    }
+
     /// Returns all immediate entries from all root directories.
     /// Entries from later roots do not override earlier ones in this list.
     #[doc(hidden)]
@@ -498,6 +2162,94 @@ impl DirSet {
         None
     }
 
+    /// Reads each of `names` with override precedence, returning the name and contents of each
+    /// in input order. A missing name yields an error of kind `NotFound` in its slot without
+    /// affecting the others.
+    pub fn read_many(&self, names: &[&str]) -> Vec<std::io::Result<(String, Vec<u8>)>> {
+        names
+            .iter()
+            .map(|name| {
+                let file = self.get_file(name).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("file not found: {name}"),
+                    )
+                })?;
+                Ok((name.to_string(), file.read_bytes()?))
+            })
+            .collect()
+    }
+
+    /// Reads the highest-precedence file with the given name through `transform`, as in
+    /// [`File::read_str_with`], so every served file can share one processing step.
+    /// Returns an error of kind `NotFound` if no root contains the file.
+    pub fn read_str_with(
+        &self,
+        name: &str,
+        transform: impl Fn(&str) -> String,
+    ) -> std::io::Result<String> {
+        let file = self.get_file(name).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("file not found: {name}"),
+            )
+        })?;
+        file.read_str_with(transform)
+    }
+
+    /// Looks `name` up in every root and returns the candidate that `prefer` ranks highest,
+    /// e.g. the newest or largest copy, instead of relying on position alone. Candidates that
+    /// compare equal fall back to positional precedence, so the later root wins a tie.
+    pub fn get_file_by(
+        &self,
+        name: &str,
+        prefer: impl Fn(&File, &File) -> std::cmp::Ordering,
+    ) -> Option<File> {
+        self.dirs
+            .iter()
+            .filter_map(|dir| dir.get_file(name))
+            .max_by(|a, b| prefer(a, b))
+    }
+
+    /// Tries each of `names` in order and returns the highest-precedence file for the first one
+    /// found in any root. Name preference is outermost: `favicon.ico` in the first root wins
+    /// over `favicon.png` in a later one.
+    pub fn get_first(&self, names: &[&str]) -> Option<File> {
+        names.iter().find_map(|name| self.get_file(name))
+    }
+
+    /// Resolves a request path for a single-page app: the highest-precedence file at `path` if
+    /// one exists, and otherwise `index` (typically `index.html`), so client-side routes such as
+    /// `/settings/profile` load the app. Paths under one of `asset_prefixes` (e.g. `assets`)
+    /// never fall back, so a missing script or image is still `None` rather than HTML.
+    /// Names are normalized as in [`Dir::get_file`]; a path that would leave the roots is `None`.
+    pub fn get_file_spa(&self, path: &str, index: &str, asset_prefixes: &[&str]) -> Option<File> {
+        let name = lookup_name(path)?;
+        if let Some(file) = self.get_file(&name) {
+            return Some(file);
+        }
+        let is_asset = asset_prefixes.iter().any(|prefix| {
+            lookup_name(prefix).is_some_and(|prefix| {
+                !prefix.is_empty()
+                    && name
+                        .strip_prefix(&prefix)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+        });
+        if is_asset { None } else { self.get_file(index) }
+    }
+
+    /// Like [`DirSet::get_file`], but also returns the index in `dirs` of the root that provided the file.
+    pub fn get_file_origin(&self, name: &str) -> Option<(usize, File)> {
+        self.dirs
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, dir)| dir.get_file(name).map(|file| (index, file)))
+    }
+
+    /// Returns the directory with the given name from the highest-precedence root that has it.
+    /// Leading and trailing slashes are handled as in [`Dir::get_dir`].
     pub fn get_dir(&self, name: &str) -> Option<Dir> {
         for dir in self.dirs.iter().rev() {
             if let Some(subdir) = dir.get_dir(name) {
@@ -509,17 +2261,18 @@ impl DirSet {
 
     /// Recursively walks all files in all root directories.
     /// Files with the same relative path from different roots are all included.
-    pub fn walk(&self) -> impl Iterator<Item = File> {
-        let mut queue: Vec<DirEntry> = Vec::with_capacity(self.dirs.len() * 128); // Assuming an average of 128 entries per directory
+    /// Stops [`DEFAULT_WALK_DEPTH`] levels below each root, like [`Dir::walk`].
+    pub fn walk(&self) -> impl Iterator<Item = File> + Send {
+        let mut queue: Vec<(DirEntry, usize)> = Vec::with_capacity(self.dirs.len() * 128); // Assuming an average of 128 entries per directory
         for dir in self.dirs.iter() {
-            queue.push(DirEntry::from_dir(dir.clone()));
+            queue.push((DirEntry::from_dir(dir.clone()), 0));
         }
         std::iter::from_fn(move || {
-            while let Some(entry) = queue.pop() {
+            while let Some((entry, depth)) = queue.pop() {
                 match entry.inner {
                     InnerEntry::File(file) => return Some(File { inner: file }),
                     InnerEntry::Dir(dir) => {
-                        for child in (Dir { inner: dir }).entries().into_iter().rev() {
+                        for child in (Dir { inner: dir }).child_entries(depth).into_iter().rev() {
                             queue.push(child);
                         }
                     }
@@ -529,16 +2282,130 @@ impl DirSet {
         })
     }
 
+    /// Returns the `/`-separated relative path of every file visible with override semantics, sorted.
+    pub fn paths_override(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.walk_override().map(|file| file.rel_url()).collect();
+        paths.sort();
+        paths
+    }
+
+    /// Counts `(dirs, files)` across all roots, counting each relative path once
+    /// as [`DirSet::walk_override`] would.
+    pub fn count_entries_override(&self) -> (usize, usize) {
+        let mut dirs = std::collections::HashSet::new();
+        let mut files = std::collections::HashSet::new();
+        for dir in &self.dirs {
+            for entry in dir.walk_entries_keep_empty() {
+                if entry.is_dir() {
+                    dirs.insert(entry.path().to_owned());
+                } else {
+                    files.insert(entry.path().to_owned());
+                }
+            }
+        }
+        (dirs.len(), files.len())
+    }
+
+    /// Writes the winning file for every relative path, as seen by [`DirSet::walk_override`],
+    /// below `dest`, creating intermediate directories and overwriting existing files. This
+    /// bakes a layered set (base, theme, user overrides) into one deployable directory.
+    /// Returns the written paths. Stops at the first IO error.
+    pub fn extract_override_to(&self, dest: &std::path::Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for file in self.walk_override() {
+            let target = dest.join(file.path());
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, file.read_bytes()?)?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+
+    /// Reads every file visible through [`DirSet::walk_override`] once, so the OS page cache is
+    /// warm before the first request. Returns the number of files visited. Embedded and
+    /// in-memory files are counted but not read. Stops at the first IO error.
+    pub fn warm(&self) -> std::io::Result<usize> {
+        let mut count = 0;
+        for file in self.walk_override() {
+            if let InnerFile::Path { .. } = &file.inner {
+                for chunk in file.chunks(64 * 1024) {
+                    chunk?;
+                }
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Like [`Dir::tree_hash`], over the files visible through [`DirSet::walk_override`].
+    /// A set with a single top-level directory hashes the same as that directory.
+    pub fn tree_hash_override(&self) -> std::io::Result<String> {
+        tree_hash(self.walk_override().map(|file| (file.rel_url(), file)))
+    }
+
+    /// Returns the newest modification time among the files visible through
+    /// [`DirSet::walk_override`]. Embedded files report their build-time mtime and dynamic files
+    /// are stat'ed on disk; embedded files built without timestamps count as
+    /// [`std::time::UNIX_EPOCH`]. An empty set returns [`std::time::UNIX_EPOCH`].
+    pub fn latest_modified(&self) -> std::io::Result<std::time::SystemTime> {
+        let mut latest = std::time::UNIX_EPOCH;
+        for file in self.walk_override() {
+            latest = latest.max(file.metadata_or_epoch()?.modified);
+        }
+        Ok(latest)
+    }
+
+    /// Bakes the layers into a single [`Dir`] in which every relative path already resolves to
+    /// its highest-precedence file, so later lookups skip the per-layer search. The files are
+    /// linked, not copied, so edits on disk are still read; the result is rooted at the first
+    /// layer. An empty set flattens to an empty embedded directory.
+    pub fn flatten(&self) -> Dir {
+        let Some(first) = self.dirs.first() else {
+            return Dir::from_embedded(include_dir::Dir::new("", &[]), "");
+        };
+        let files = self
+            .walk_override()
+            .map(|file| {
+                let key = normalize_path(file.path());
+                (key, overlay::OverlayFile::Linked(file.inner))
+            })
+            .collect();
+        Dir {
+            inner: InnerDir::Overlay {
+                base: Arc::new(first.inner.clone()),
+                files: overlay::OverlayFiles::from_map(files),
+            },
+        }
+    }
+
+    /// Walks like [`DirSet::walk_override`], but also skips files whose contents are identical
+    /// to a file already yielded, e.g. the same image stored under two names.
+    ///
+    /// Every file is read in full to compute its SHA-256, so this costs one pass over all
+    /// contents; dynamic trees pay that in disk IO. Files that cannot be read are yielded
+    /// anyway, so the error surfaces when the caller reads them.
+    pub fn walk_dedup_content(&self) -> impl Iterator<Item = File> + Send {
+        let mut seen = std::collections::HashSet::new();
+        self.walk_override()
+            .filter(move |file| match file.sha256() {
+                Ok(digest) => seen.insert(digest),
+                Err(_) => true,
+            })
+    }
+
     /// Recursively walks all files, yielding only the highest-precedence file for each relative path.
     /// This implements the override behaviour: later roots take precedence over earlier ones.
-    pub fn walk_override(&self) -> impl Iterator<Item = File> {
+    /// Stops [`DEFAULT_WALK_DEPTH`] levels below each root, like [`Dir::walk`].
+    pub fn walk_override(&self) -> impl Iterator<Item = File> + Send {
         let mut history = std::collections::HashSet::new();
-        let mut stack: Vec<DirEntry> = Vec::with_capacity(self.dirs.len() * 128); // DFS uses stack
+        let mut stack: Vec<(DirEntry, usize)> = Vec::with_capacity(self.dirs.len() * 128); // DFS uses stack
         for dir in self.dirs.iter() {
-            stack.push(DirEntry::from_dir(dir.clone()));
+            stack.push((DirEntry::from_dir(dir.clone()), 0));
         }
         std::iter::from_fn(move || {
-            while let Some(entry) = stack.pop() {
+            while let Some((entry, depth)) = stack.pop() {
                 match entry.inner {
                     InnerEntry::File(file) => {
                         if history.insert(file.path().to_owned()) {
@@ -547,7 +2414,7 @@ impl DirSet {
                     }
                     InnerEntry::Dir(dir) => {
                         // Push children in reverse order to preserve order in DFS
-                        let children = Dir { inner: dir }.entries();
+                        let children = Dir { inner: dir }.child_entries(depth);
                         for child in children.into_iter() {
                             stack.push(child);
                         }
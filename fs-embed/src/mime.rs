@@ -0,0 +1,81 @@
+use crate::{DirSet, File};
+
+/// The MIME types used by [`File::content_type`], keyed by lowercase file extension.
+pub static DEFAULT_MIME_TYPES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "html" => "text/html",
+    "htm" => "text/html",
+    "css" => "text/css",
+    "js" => "text/javascript",
+    "mjs" => "text/javascript",
+    "json" => "application/json",
+    "map" => "application/json",
+    "webmanifest" => "application/manifest+json",
+    "xml" => "application/xml",
+    "txt" => "text/plain",
+    "md" => "text/markdown",
+    "csv" => "text/csv",
+    "svg" => "image/svg+xml",
+    "png" => "image/png",
+    "jpg" => "image/jpeg",
+    "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "ico" => "image/x-icon",
+    "wasm" => "application/wasm",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    "pdf" => "application/pdf",
+    "zip" => "application/zip",
+    "gz" => "application/gzip",
+    "mp3" => "audio/mpeg",
+    "ogg" => "audio/ogg",
+    "wav" => "audio/wav",
+    "mp4" => "video/mp4",
+    "webm" => "video/webm",
+};
+
+impl File {
+    /// Returns the MIME type for this file's extension from [`DEFAULT_MIME_TYPES`],
+    /// or `None` if the extension is missing or unknown. Matching is case-insensitive.
+    pub fn content_type(&self) -> Option<&'static str> {
+        self.content_type_with(&phf::Map::new())
+    }
+
+    /// Detects the MIME type from the file's leading magic bytes instead of its extension,
+    /// for files with a wrong or missing extension. Returns `None` if the type is not
+    /// recognized or the file cannot be read.
+    #[cfg(feature = "infer")]
+    pub fn sniff_content_type(&self) -> Option<&'static str> {
+        /// Enough bytes for every signature `infer` checks, including tar's header.
+        const SNIFF_LEN: usize = 8192;
+        let prefix = self.read_prefix(SNIFF_LEN).ok()?;
+        infer::get(&prefix).map(|kind| kind.mime_type())
+    }
+
+    /// Like [`File::content_type`], but looks the extension up in `overrides` first, so apps
+    /// can add or replace mappings. Keys must be lowercase extensions without the dot.
+    pub fn content_type_with(
+        &self,
+        overrides: &phf::Map<&'static str, &'static str>,
+    ) -> Option<&'static str> {
+        let ext = self.extension()?.to_ascii_lowercase();
+        overrides
+            .get(ext.as_str())
+            .or_else(|| DEFAULT_MIME_TYPES.get(ext.as_str()))
+            .copied()
+    }
+}
+
+impl DirSet {
+    /// Walks like [`DirSet::walk_override`], pairing each file with its
+    /// [`File::content_type`], e.g. to build a preload manifest.
+    pub fn walk_override_typed(&self) -> impl Iterator<Item = (File, Option<&'static str>)> + Send {
+        self.walk_override().map(|file| {
+            let content_type = file.content_type();
+            (file, content_type)
+        })
+    }
+}
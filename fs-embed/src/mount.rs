@@ -0,0 +1,140 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{Dir, DirEntry, EmbedRoot, File, InnerDir, InnerEntry, InnerFile, Origin};
+
+/// Where a dynamic directory sits in a tree embedded with `mount`.
+#[derive(Debug, Clone)]
+pub(crate) struct Mount {
+    /// The mount prefix the embedded tree nests its on-disk root under.
+    pub(crate) prefix: Arc<Path>,
+    /// The directory's relative path, including the prefix. Above the on-disk root this is a
+    /// proper ancestor of `prefix`, and the directory holds nothing but the next level down.
+    pub(crate) rel: Arc<Path>,
+}
+
+/// The dynamic counterpart of the embedded directory at relative path `rel` in the tree
+/// described by `root`, with the same relative paths as the embedded one.
+pub(crate) fn dynamic(root: &EmbedRoot, rel: &Path) -> InnerDir {
+    let disk_root: Arc<Path> = Arc::from(Path::new(root.path));
+    if root.mount.is_empty() {
+        return InnerDir::Path {
+            path: Arc::from(disk_root.join(rel)),
+            root: disk_root,
+            origin: Origin::Embedded,
+            mount: None,
+        };
+    }
+    let prefix: Arc<Path> = Arc::from(Path::new(root.mount));
+    dir_at(&disk_root, Origin::Embedded, &prefix, rel)
+}
+
+/// Lists a mounted directory: the next mount level above the on-disk root, or the directory on
+/// disk with the prefix added to every relative path.
+pub(crate) fn entries(
+    root: &Arc<Path>,
+    path: &Path,
+    origin: Origin,
+    mount: &Mount,
+) -> Vec<DirEntry> {
+    let next = mount
+        .prefix
+        .strip_prefix(&mount.rel)
+        .ok()
+        .and_then(|below| below.components().next());
+    if let Some(next) = next {
+        let rel = mount.rel.join(next);
+        return vec![DirEntry {
+            inner: InnerEntry::Dir(dir_at(root, origin, &mount.prefix, &rel)),
+        }];
+    }
+
+    let on_disk = Dir {
+        inner: InnerDir::Path {
+            root: root.clone(),
+            path: Arc::from(path),
+            origin,
+            mount: None,
+        },
+    };
+    on_disk
+        .entries()
+        .into_iter()
+        .map(|entry| {
+            let rel = mount.rel.join(entry.path().file_name().unwrap_or_default());
+            let inner = match entry.inner {
+                InnerEntry::File(file) => InnerEntry::File(mounted(file, rel)),
+                InnerEntry::Dir(_) => InnerEntry::Dir(dir_at(root, origin, &mount.prefix, &rel)),
+            };
+            DirEntry { inner }
+        })
+        .collect()
+}
+
+pub(crate) fn get_file(
+    root: &Arc<Path>,
+    origin: Origin,
+    mount: &Mount,
+    name: &str,
+) -> Option<File> {
+    let rel = mount.rel.join(name);
+    let path = root.join(rel.strip_prefix(&mount.prefix).ok()?);
+    path.is_file().then(|| File {
+        inner: InnerFile::Path {
+            root: root.clone(),
+            path,
+            origin,
+            mounted: Some(rel),
+        },
+    })
+}
+
+pub(crate) fn get_dir(root: &Arc<Path>, origin: Origin, mount: &Mount, name: &str) -> Option<Dir> {
+    let rel = mount.rel.join(name);
+    let exists = match rel.strip_prefix(&mount.prefix) {
+        Ok(inside) => root.join(inside).is_dir(),
+        Err(_) => mount.prefix.starts_with(&rel),
+    };
+    exists.then(|| Dir {
+        inner: dir_at(root, origin, &mount.prefix, &rel),
+    })
+}
+
+/// The directory at relative path `rel`. Levels above the on-disk root have no location of their
+/// own and report the root as their absolute path.
+pub(crate) fn dir_at(
+    disk_root: &Arc<Path>,
+    origin: Origin,
+    prefix: &Arc<Path>,
+    rel: &Path,
+) -> InnerDir {
+    let path = match rel.strip_prefix(prefix) {
+        Ok(inside) => disk_root.join(inside),
+        Err(_) => disk_root.to_path_buf(),
+    };
+    InnerDir::Path {
+        root: disk_root.clone(),
+        path: Arc::from(path),
+        origin,
+        mount: Some(Mount {
+            prefix: prefix.clone(),
+            rel: Arc::from(rel),
+        }),
+    }
+}
+
+fn mounted(file: InnerFile, rel: PathBuf) -> InnerFile {
+    match file {
+        InnerFile::Path {
+            root, path, origin, ..
+        } => InnerFile::Path {
+            root,
+            path,
+            origin,
+            mounted: Some(rel),
+        },
+        other => other,
+    }
+}
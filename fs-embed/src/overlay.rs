@@ -0,0 +1,364 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{
+    Dir, DirEntry, File, InnerDir, InnerEntry, InnerFile, lookup_name, mount, normalize_path,
+};
+
+/// Contents injected with [`Dir::with_file`].
+#[derive(Debug)]
+pub(crate) struct MemoryFile {
+    pub(crate) contents: Vec<u8>,
+    pub(crate) modified: SystemTime,
+}
+
+/// A file held by an overlay: contents added with [`Dir::with_file`], or a handle to a file
+/// from another directory, as collected by [`DirSet::flatten`](crate::DirSet::flatten).
+#[derive(Debug, Clone)]
+pub(crate) enum OverlayFile {
+    Memory(Arc<MemoryFile>),
+    Linked(InnerFile),
+}
+
+type Layer = Arc<BTreeMap<String, OverlayFile>>;
+
+/// Overlay files keyed by their `/`-separated path relative to the tree root.
+///
+/// The files are kept in a few shared layers, newest first, so [`Dir::with_file`] copies a
+/// small layer instead of the whole map. Each layer is under half the size of the one after
+/// it; adding a file merges layers until that holds again, which keeps both the number of
+/// layers and the amortized copying logarithmic in the number of files.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OverlayFiles {
+    layers: Vec<Layer>,
+}
+
+impl OverlayFiles {
+    pub(crate) fn from_map(files: BTreeMap<String, OverlayFile>) -> Self {
+        OverlayFiles {
+            layers: vec![Arc::new(files)],
+        }
+    }
+
+    /// Returns a copy with `key` added or replaced, sharing the older layers.
+    fn with(&self, key: String, file: OverlayFile) -> Self {
+        let mut newest = BTreeMap::from([(key, file)]);
+        let mut older = self.layers.iter();
+        let mut rest = Vec::new();
+        for layer in older.by_ref() {
+            if newest.len() * 2 <= layer.len() {
+                rest.push(layer.clone());
+                break;
+            }
+            let mut merged = (**layer).clone();
+            merged.append(&mut newest);
+            newest = merged;
+        }
+        rest.extend(older.cloned());
+        let mut layers = vec![Arc::new(newest)];
+        layers.append(&mut rest);
+        OverlayFiles { layers }
+    }
+
+    fn get(&self, key: &str) -> Option<&OverlayFile> {
+        self.layers.iter().find_map(|layer| layer.get(key))
+    }
+
+    /// Iterates the files in key order, each key once with its newest file.
+    fn iter(&self) -> impl Iterator<Item = (&String, &OverlayFile)> {
+        let mut merged = BTreeMap::new();
+        for layer in self.layers.iter().rev() {
+            merged.extend(layer.iter());
+        }
+        merged.into_iter()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.layers.iter().flat_map(|layer| layer.keys())
+    }
+
+    /// Returns true if both share the same layers, i.e. one is a clone of the other.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        self.layers.len() == other.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(&other.layers)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+impl Dir {
+    /// Returns a copy of this directory in which `rel_path` reads as `contents`, replacing the
+    /// file at that path or adding it, together with any missing parent directories.
+    ///
+    /// Every other path falls through to this directory, which makes this a lighter alternative
+    /// to a [`DirSet`](crate::DirSet) when a single file needs customizing. The replacement is
+    /// held in memory and is kept across [`Dir::into_dynamic`].
+    ///
+    /// `rel_path` is resolved like a [`Dir::get_file`] name, so `a/../b.txt` replaces `b.txt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rel_path` names no file below this directory, e.g. `""` or `../b.txt`.
+    pub fn with_file(&self, rel_path: &str, contents: Vec<u8>) -> Dir {
+        let key = child_key(self.path(), rel_path)
+            .unwrap_or_else(|| panic!("with_file: `{rel_path}` is not a file below the directory"));
+        let file = OverlayFile::Memory(Arc::new(MemoryFile {
+            contents,
+            modified: SystemTime::now(),
+        }));
+        let (base, files) = match &self.inner {
+            InnerDir::Overlay { base, files } => ((**base).clone(), files.with(key, file)),
+            other => (other.clone(), OverlayFiles::default().with(key, file)),
+        };
+        Dir {
+            inner: InnerDir::Overlay {
+                base: Arc::new(base),
+                files,
+            },
+        }
+    }
+
+    /// Releases spare capacity held by the contents of files added with [`Dir::with_file`],
+    /// which matters for long-running servers that keep replacing files in an overlay.
+    ///
+    /// Only storage this `Dir` owns exclusively is shrunk; contents still shared with a clone
+    /// are left alone rather than duplicated. This is a no-op for embedded and dynamic
+    /// directories.
+    pub fn compact(&mut self) {
+        compact(&mut self.inner);
+    }
+}
+
+fn compact(dir: &mut InnerDir) {
+    match dir {
+        InnerDir::Overlay { base, files } => {
+            if let Some(base) = Arc::get_mut(base) {
+                compact(base);
+            }
+            for layer in &mut files.layers {
+                let Some(layer) = Arc::get_mut(layer) else {
+                    continue;
+                };
+                for file in layer.values_mut() {
+                    if let OverlayFile::Memory(memory) = file
+                        && let Some(memory) = Arc::get_mut(memory)
+                    {
+                        memory.contents.shrink_to_fit();
+                    }
+                }
+            }
+        }
+        InnerDir::Filter { base, .. } | InnerDir::DiskOverlay { base, .. } => {
+            if let Some(base) = Arc::get_mut(base) {
+                compact(base);
+            }
+        }
+        InnerDir::Embed(..) | InnerDir::Path { .. } => {}
+    }
+}
+
+/// Lists `base`, swapping in overlay files and adding the ones (and their parent
+/// directories) that `base` does not have.
+pub(crate) fn entries(base: &InnerDir, files: &OverlayFiles) -> Vec<DirEntry> {
+    let dir_key = normalize_path(base.path());
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for entry in (Dir {
+        inner: base.clone(),
+    })
+    .entries()
+    {
+        let inner = match entry.inner {
+            InnerEntry::File(file) => {
+                let key = normalize_path(file.path());
+                let file = match files.get(&key) {
+                    Some(overlay) => overlay_file(&key, overlay, base),
+                    None => file,
+                };
+                seen.insert(key);
+                InnerEntry::File(file)
+            }
+            InnerEntry::Dir(dir) => {
+                seen.insert(normalize_path(dir.path()));
+                InnerEntry::Dir(wrap(dir, files))
+            }
+        };
+        entries.push(DirEntry { inner });
+    }
+
+    for (key, overlay) in files.iter() {
+        let Some(rest) = strip_dir(key, &dir_key) else {
+            continue;
+        };
+        match rest.split_once('/') {
+            None => {
+                if seen.insert(key.clone()) {
+                    entries.push(DirEntry {
+                        inner: InnerEntry::File(overlay_file(key, overlay, base)),
+                    });
+                }
+            }
+            Some((name, _)) => {
+                let subdir = join_key(&dir_key, name);
+                if !seen.contains(&subdir) {
+                    entries.push(DirEntry {
+                        inner: InnerEntry::Dir(synthetic_dir(base, &subdir, files)),
+                    });
+                    seen.insert(subdir);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Looks up `name` in the overlay files first, then in `base`.
+pub(crate) fn get_file(base: &InnerDir, files: &OverlayFiles, name: &str) -> Option<File> {
+    let key = child_key(base.path(), name)?;
+    match files.get(&key) {
+        Some(overlay) => Some(File {
+            inner: overlay_file(&key, overlay, base),
+        }),
+        None => Dir {
+            inner: base.clone(),
+        }
+        .get_file(name),
+    }
+}
+
+/// Looks up the subdirectory `name` in `base`, falling back to a directory that only
+/// exists because overlay files were added below it.
+pub(crate) fn get_dir(base: &InnerDir, files: &OverlayFiles, name: &str) -> Option<Dir> {
+    if let Some(dir) = (Dir {
+        inner: base.clone(),
+    })
+    .get_dir(name)
+    {
+        return Some(Dir {
+            inner: wrap(dir.inner, files),
+        });
+    }
+    let key = child_key(base.path(), name)?;
+    if has_files_under(files, &key) {
+        Some(Dir {
+            inner: synthetic_dir(base, &key, files),
+        })
+    } else {
+        None
+    }
+}
+
+/// Keeps the overlay on a subdirectory only if some overlay file lives below it.
+fn wrap(dir: InnerDir, files: &OverlayFiles) -> InnerDir {
+    if has_files_under(files, &normalize_path(dir.path())) {
+        InnerDir::Overlay {
+            base: Arc::new(dir),
+            files: files.clone(),
+        }
+    } else {
+        dir
+    }
+}
+
+/// A directory that only holds overlay files, over an empty directory of the same backend
+/// as `base`, so its relative path and embeddedness line up with the rest of the tree.
+fn synthetic_dir(base: &InnerDir, key: &str, files: &OverlayFiles) -> InnerDir {
+    InnerDir::Overlay {
+        base: Arc::new(empty_dir(base, key)),
+        files: files.clone(),
+    }
+}
+
+/// An empty directory at relative path `key` in the backend below `base`'s wrappers. Dynamic
+/// ones point where the directory would be on disk.
+fn empty_dir(base: &InnerDir, key: &str) -> InnerDir {
+    match base {
+        InnerDir::Embed(_, root) => InnerDir::Embed(include_dir::Dir::new(intern(key), &[]), *root),
+        InnerDir::Path {
+            root,
+            origin,
+            mount: Some(mount),
+            ..
+        } => mount::dir_at(root, *origin, &mount.prefix, std::path::Path::new(key)),
+        InnerDir::Path {
+            root,
+            origin,
+            mount: None,
+            ..
+        } => InnerDir::Path {
+            root: root.clone(),
+            path: Arc::from(root.join(key)),
+            origin: *origin,
+            mount: None,
+        },
+        InnerDir::Overlay { base, .. }
+        | InnerDir::Filter { base, .. }
+        | InnerDir::DiskOverlay { base, .. } => empty_dir(base, key),
+    }
+}
+
+/// Returns `key` as a string that lives for the rest of the process, allocating each distinct
+/// key once, since embedded directories need a `'static` path.
+fn intern(key: &str) -> &'static str {
+    static KEYS: std::sync::OnceLock<std::sync::RwLock<HashSet<&'static str>>> =
+        std::sync::OnceLock::new();
+    let keys = KEYS.get_or_init(Default::default);
+    if let Some(interned) = keys.read().unwrap_or_else(|e| e.into_inner()).get(key) {
+        return interned;
+    }
+    let mut keys = keys.write().unwrap_or_else(|e| e.into_inner());
+    match keys.get(key) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(key.to_owned().into_boxed_str());
+            keys.insert(interned);
+            interned
+        }
+    }
+}
+
+fn overlay_file(key: &str, overlay: &OverlayFile, base: &InnerDir) -> InnerFile {
+    match overlay {
+        OverlayFile::Memory(memory) => InnerFile::Memory {
+            path: PathBuf::from(key),
+            file: memory.clone(),
+            origin: base.origin(),
+        },
+        OverlayFile::Linked(file) => file.clone(),
+    }
+}
+
+fn has_files_under(files: &OverlayFiles, dir_key: &str) -> bool {
+    files
+        .keys()
+        .any(|key| strip_dir(key, dir_key).is_some_and(|rest| !rest.is_empty()))
+}
+
+fn strip_dir<'a>(key: &'a str, dir_key: &str) -> Option<&'a str> {
+    if dir_key.is_empty() {
+        Some(key)
+    } else {
+        key.strip_prefix(dir_key)?.strip_prefix('/')
+    }
+}
+
+fn join_key(dir_key: &str, name: &str) -> String {
+    if dir_key.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir_key}/{name}")
+    }
+}
+
+/// The key of `name` below `dir`, resolved like a [`Dir::get_file`] name. Returns `None` if
+/// `name` names nothing below `dir`.
+fn child_key(dir: &std::path::Path, name: &str) -> Option<String> {
+    let name = lookup_name(name).filter(|name| !name.is_empty())?;
+    Some(join_key(&normalize_path(dir), &name))
+}
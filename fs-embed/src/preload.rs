@@ -0,0 +1,85 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{DirSet, lookup_name};
+
+/// A file held in a [`PreloadedSet`], with its response metadata computed up front.
+#[derive(Debug, Clone)]
+pub struct PreloadedFile {
+    contents: Arc<[u8]>,
+    content_type: Option<&'static str>,
+    etag: String,
+}
+
+impl PreloadedFile {
+    /// Returns the file contents.
+    pub fn contents(&self) -> &Arc<[u8]> {
+        &self.contents
+    }
+
+    /// Returns the MIME type from [`File::content_type`](crate::File::content_type).
+    pub fn content_type(&self) -> Option<&'static str> {
+        self.content_type
+    }
+
+    /// Returns the strong ETag, as [`File::etag`](crate::File::etag) would compute it.
+    pub fn etag(&self) -> &str {
+        &self.etag
+    }
+}
+
+/// An in-memory snapshot of a [`DirSet`], created by [`DirSet::preload`].
+///
+/// Every override-resolved file is read once, so lookups never touch the disk or the set
+/// again. Clones share the snapshot, and it is `Send + Sync` for use across request handlers.
+/// Later changes on disk are not seen.
+#[derive(Debug, Clone, Default)]
+pub struct PreloadedSet {
+    files: Arc<HashMap<String, PreloadedFile>>,
+}
+
+impl PreloadedSet {
+    /// Returns the contents of the file with the given relative path.
+    /// A single leading `/` is ignored.
+    pub fn get(&self, path: &str) -> Option<Arc<[u8]>> {
+        self.get_file(path).map(|file| file.contents.clone())
+    }
+
+    /// Returns the file with the given relative path, including its content type and ETag.
+    /// Paths are resolved like [`DirSet::get_file`], so one that would leave the set's
+    /// directories returns `None`.
+    pub fn get_file(&self, path: &str) -> Option<&PreloadedFile> {
+        match self.files.get(path.strip_prefix('/').unwrap_or(path)) {
+            Some(file) => Some(file),
+            None => self.files.get(&lookup_name(path)?),
+        }
+    }
+
+    /// Returns the number of preloaded files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if no files were preloaded.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl DirSet {
+    /// Reads every file visible through [`DirSet::walk_override`] into a [`PreloadedSet`],
+    /// keyed by its `/`-separated relative path. Stops at the first IO error.
+    pub fn preload(&self) -> std::io::Result<PreloadedSet> {
+        let mut files = HashMap::new();
+        for file in self.walk_override() {
+            let preloaded = PreloadedFile {
+                contents: Arc::from(file.read_bytes()?),
+                content_type: file.content_type(),
+                etag: file.etag()?,
+            };
+            files.insert(file.rel_url(), preloaded);
+        }
+        Ok(PreloadedSet {
+            files: Arc::new(files),
+        })
+    }
+}
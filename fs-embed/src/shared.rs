@@ -0,0 +1,15 @@
+use bytes::Bytes;
+
+use crate::{File, InnerFile};
+
+impl File {
+    /// Reads the file contents as a [`Bytes`] buffer for HTTP response bodies.
+    /// Embedded files are wrapped with [`Bytes::from_static`], so no copy is made;
+    /// dynamic files are read from disk into a new buffer.
+    pub fn read_bytes_shared(&self) -> std::io::Result<Bytes> {
+        match &self.inner {
+            InnerFile::Embed(file, ..) => Ok(Bytes::from_static(file.contents())),
+            InnerFile::Path { .. } | InnerFile::Memory { .. } => self.read_bytes().map(Bytes::from),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{Dir, DirSet, File};
+
+/// A file-like handle exposed by a [`Source`].
+pub trait FileLike {
+    /// Returns the relative path of this file.
+    fn path(&self) -> &std::path::Path;
+
+    /// Reads the file contents as bytes.
+    fn read_bytes(&self) -> std::io::Result<Vec<u8>>;
+
+    /// Reads the file contents as a UTF-8 string.
+    fn read_str(&self) -> std::io::Result<String>;
+
+    /// Returns true if this file is embedded in the binary.
+    fn is_embedded(&self) -> bool;
+}
+
+/// A collection of files that can be looked up by relative path and walked.
+/// Lets libraries be generic over a single [`Dir`] or a layered [`DirSet`].
+pub trait Source {
+    /// The file type returned by this source.
+    type File: FileLike;
+
+    /// Returns the file with the given relative path, if it exists.
+    fn get_file(&self, name: &str) -> Option<Self::File>;
+
+    /// Recursively walks every file visible through this source.
+    fn walk(&self) -> impl Iterator<Item = Self::File>;
+}
+
+impl FileLike for File {
+    fn path(&self) -> &std::path::Path {
+        File::path(self)
+    }
+
+    fn read_bytes(&self) -> std::io::Result<Vec<u8>> {
+        File::read_bytes(self)
+    }
+
+    fn read_str(&self) -> std::io::Result<String> {
+        File::read_str(self)
+    }
+
+    fn is_embedded(&self) -> bool {
+        File::is_embedded(self)
+    }
+}
+
+impl Source for Dir {
+    type File = File;
+
+    fn get_file(&self, name: &str) -> Option<File> {
+        Dir::get_file(self, name)
+    }
+
+    fn walk(&self) -> impl Iterator<Item = File> {
+        Dir::walk(self)
+    }
+}
+
+/// Walking a `DirSet` as a [`Source`] uses override semantics, matching `get_file`.
+impl Source for DirSet {
+    type File = File;
+
+    fn get_file(&self, name: &str) -> Option<File> {
+        DirSet::get_file(self, name)
+    }
+
+    fn walk(&self) -> impl Iterator<Item = File> {
+        self.walk_override()
+    }
+}
@@ -0,0 +1,51 @@
+//! Helpers for asserting that embedded and dynamic modes agree.
+
+use crate::{Dir, normalize_path};
+
+/// Returns `(embedded, dynamic)` views of the same tree: `embedded` itself and its
+/// [`Dir::into_dynamic`] counterpart, read from the on-disk source directory.
+///
+/// # Panics
+///
+/// Panics if `embedded` is not an embedded directory.
+pub fn both_modes(embedded: Dir) -> (Dir, Dir) {
+    assert!(
+        embedded.is_embedded(),
+        "both_modes expects an embedded directory, got {:?}",
+        embedded.absolute_path()
+    );
+    let dynamic = embedded.clone().into_dynamic();
+    (embedded, dynamic)
+}
+
+/// Asserts that `a` and `b` contain the same files with the same contents, comparing
+/// paths relative to each directory.
+///
+/// # Panics
+///
+/// Panics with the first differing path if the trees diverge or a file cannot be read.
+pub fn assert_dirs_equivalent(a: &Dir, b: &Dir) {
+    let a_files: Vec<_> = a.walk_sorted().collect();
+    let b_files: Vec<_> = b.walk_sorted().collect();
+    let relative = |dir: &Dir, file: &crate::File| {
+        normalize_path(file.path().strip_prefix(dir.path()).unwrap_or(file.path()))
+    };
+    let a_paths: Vec<String> = a_files.iter().map(|f| relative(a, f)).collect();
+    let b_paths: Vec<String> = b_files.iter().map(|f| relative(b, f)).collect();
+    assert_eq!(
+        a_paths,
+        b_paths,
+        "file lists differ between {:?} and {:?}",
+        a.absolute_path(),
+        b.absolute_path()
+    );
+    for ((a_file, b_file), rel) in a_files.iter().zip(&b_files).zip(&a_paths) {
+        let a_bytes = a_file
+            .read_bytes()
+            .unwrap_or_else(|e| panic!("failed to read {rel}: {e}"));
+        let b_bytes = b_file
+            .read_bytes()
+            .unwrap_or_else(|e| panic!("failed to read {rel}: {e}"));
+        assert!(a_bytes == b_bytes, "contents differ for {rel}");
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{DirSet, normalize_path};
+
+/// A problem found by [`DirSet::validate`]. `layer` is the index of the root in
+/// [`DirSet::dirs`] where the problem occurs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ValidationIssue {
+    /// A file in an override layer that no lower layer provides, which is often a typo.
+    UnmatchedOverride { layer: usize, path: PathBuf },
+    /// Several files in one layer that share a path once separators and case are normalized.
+    DuplicatePath { layer: usize, path: PathBuf },
+    /// A file whose relative path is not valid UTF-8.
+    NonUtf8Path { layer: usize, path: PathBuf },
+}
+
+impl ValidationIssue {
+    /// Returns the relative path the issue is about.
+    pub fn path(&self) -> &Path {
+        match self {
+            ValidationIssue::UnmatchedOverride { path, .. }
+            | ValidationIssue::DuplicatePath { path, .. }
+            | ValidationIssue::NonUtf8Path { path, .. } => path,
+        }
+    }
+
+    /// Returns a short human-readable reason for the issue.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ValidationIssue::UnmatchedOverride { .. } => "overrides no file in a lower layer",
+            ValidationIssue::DuplicatePath { .. } => {
+                "collides with another path after normalization"
+            }
+            ValidationIssue::NonUtf8Path { .. } => "is not valid UTF-8",
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let layer = match self {
+            ValidationIssue::UnmatchedOverride { layer, .. }
+            | ValidationIssue::DuplicatePath { layer, .. }
+            | ValidationIssue::NonUtf8Path { layer, .. } => layer,
+        };
+        write!(
+            f,
+            "layer {layer}: {} {}",
+            self.path().display(),
+            self.reason()
+        )
+    }
+}
+
+impl DirSet {
+    /// Checks every layer for likely mistakes and returns the issues found, layer by layer:
+    /// non-UTF-8 paths, paths that collide after normalizing separators and case, and files in
+    /// an override layer (any root after the first) that no lower layer has.
+    /// An empty result means the set passed every check.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let mut lower: HashSet<String> = HashSet::new();
+        for (layer, dir) in self.dirs.iter().enumerate() {
+            let mut paths = Vec::new();
+            for file in dir.walk() {
+                let rel = file.path().strip_prefix(dir.path()).unwrap_or(file.path());
+                if rel.to_str().is_none() {
+                    issues.push(ValidationIssue::NonUtf8Path {
+                        layer,
+                        path: rel.to_path_buf(),
+                    });
+                }
+                paths.push((normalize_path(rel), rel.to_path_buf()));
+            }
+            paths.sort();
+
+            let mut folded = HashSet::new();
+            for (key, _) in &paths {
+                if !folded.insert(key.to_lowercase()) {
+                    issues.push(ValidationIssue::DuplicatePath {
+                        layer,
+                        path: PathBuf::from(key),
+                    });
+                }
+            }
+            if layer > 0 {
+                for (key, path) in &paths {
+                    if !lower.contains(key) {
+                        issues.push(ValidationIssue::UnmatchedOverride {
+                            layer,
+                            path: path.clone(),
+                        });
+                    }
+                }
+            }
+            lower.extend(paths.into_iter().map(|(key, _)| key));
+        }
+        issues
+    }
+}
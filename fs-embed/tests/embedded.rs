@@ -7,6 +7,14 @@ fn embedded_dir() -> Dir {
     EMBEDDED.clone()
 }
 
+/// Creates a fresh temporary directory named after the calling test.
+fn test_temp_dir(name: &str) -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix(&format!("fs_embed_test_{name}_"))
+        .tempdir()
+        .expect("create temp dir")
+}
+
 /// Checks that embedded directory entries include expected files and subdirectories.
 #[test]
 fn test_embedded_dir_entries() {
@@ -111,3 +119,595 @@ fn test_embedded_is_embedded_true() {
         assert!(entry.is_embedded());
     }
 }
+
+/// Checks that walk_under() prunes embedded subtrees outside the prefix.
+#[test]
+fn test_embedded_walk_under() {
+    let dir = embedded_dir();
+    let names: Vec<_> = dir.walk_under("subdir/subsubdir").map(|f| f.file_name().unwrap().to_string()).collect();
+    assert_eq!(names, vec!["zeta.txt".to_string()]);
+}
+
+/// Checks that origin() still reports Embedded after converting to a dynamic directory.
+#[test]
+fn test_embedded_origin_preserved_after_into_dynamic() {
+    let dir = embedded_dir();
+    assert_eq!(dir.origin(), Origin::Embedded);
+    let dynamic = dir.into_dynamic();
+    assert!(!dynamic.is_embedded());
+    assert_eq!(dynamic.origin(), Origin::Embedded);
+    let file = dynamic.get_file("subdir/gamma.txt").unwrap();
+    assert!(!file.is_embedded());
+    assert_eq!(file.origin(), Origin::Embedded);
+    for entry in dynamic.entries() {
+        assert_eq!(entry.origin(), Origin::Embedded);
+    }
+    assert_eq!(Dir::from_str("tests/data").origin(), Origin::Filesystem);
+}
+
+/// Collects sorted relative paths of every file reachable from `dir`.
+fn walk_paths(dir: &Dir) -> Vec<std::path::PathBuf> {
+    let mut paths: Vec<_> = dir.walk().map(|f| f.path().to_owned()).collect();
+    paths.sort();
+    paths
+}
+
+/// Checks that embedded and dynamic modes agree on get_file, get_dir and walk at every level.
+#[test]
+fn test_embedded_dynamic_mode_parity() {
+    assert_mode_parity(embedded_dir());
+}
+
+static MOUNTED: Dir = fs_embed!("tests/data", mount = "assets/data");
+
+/// Checks that a `mount` prefix resolves the same paths in embedded and dynamic mode.
+#[test]
+fn test_embedded_mounted_mode_parity() {
+    assert_mode_parity(MOUNTED.clone());
+    let dynamic = MOUNTED.clone().into_dynamic();
+    let file = dynamic.get_file("assets/data/subdir/gamma.txt").unwrap();
+    assert_eq!(
+        file.path(),
+        std::path::Path::new("assets/data/subdir/gamma.txt")
+    );
+    assert!(
+        file.absolute_path()
+            .ends_with("tests/data/subdir/gamma.txt")
+    );
+    assert!(dynamic.get_file("subdir/gamma.txt").is_none());
+    let siblings: Vec<_> = file.siblings().unwrap().iter().map(File::rel_url).collect();
+    assert_eq!(siblings, ["assets/data/subdir/delta.txt"]);
+    assert_eq!(dynamic.count_entries(), MOUNTED.count_entries());
+    assert!(MOUNTED.diff_embedded_vs_disk().unwrap().is_empty());
+}
+
+/// Walks `embedded` and its dynamic counterpart in lockstep, comparing every level.
+fn assert_mode_parity(embedded: Dir) {
+    let dynamic = embedded.clone().into_dynamic();
+    assert_eq!(walk_paths(&embedded), walk_paths(&dynamic));
+
+    let mut pending = vec![(embedded, dynamic)];
+    while let Some((e, d)) = pending.pop() {
+        assert_eq!(e.path(), d.path());
+        assert_eq!(walk_paths(&e), walk_paths(&d), "walk differs in {:?}", e.path());
+        for file in e.walk() {
+            let rel = file.path().strip_prefix(e.path()).unwrap().to_str().unwrap().to_string();
+            let from_dynamic = d.get_file(&rel).unwrap_or_else(|| panic!("dynamic get_file({rel}) missing in {:?}", d.path()));
+            assert_eq!(file.path(), from_dynamic.path());
+            assert_eq!(file.read_bytes().unwrap(), from_dynamic.read_bytes().unwrap());
+        }
+        for entry in e.entries().into_iter().filter(|entry| entry.is_dir()) {
+            let name = entry.path().file_name().unwrap().to_str().unwrap().to_string();
+            let sub_e = e.get_dir(&name).expect("embedded get_dir");
+            let sub_d = d.get_dir(&name).expect("dynamic get_dir");
+            pending.push((sub_e, sub_d));
+        }
+        assert!(e.get_file("notfound.txt").is_none() && d.get_file("notfound.txt").is_none());
+        assert!(e.get_dir("not_a_dir").is_none() && d.get_dir("not_a_dir").is_none());
+    }
+}
+
+static RAW: include_dir::Dir<'static> = include_dir::include_dir!("$CARGO_MANIFEST_DIR/tests/data");
+
+/// Checks that a Dir can be built directly from an existing include_dir::Dir.
+#[test]
+fn test_from_include_dir() {
+    let dir = Dir::from(&RAW);
+    assert!(dir.is_embedded());
+    assert_eq!(dir.get_file("alpha.txt").unwrap().read_str().unwrap().trim(), "Hello from alpha!");
+    let subdir = Dir::from(RAW.get_dir("subdir").unwrap());
+    assert!(subdir.get_file("gamma.txt").is_some());
+    assert_eq!(dir.walk().count(), embedded_dir().walk().count());
+}
+
+/// Checks that an index over an embedded dir resolves the same files as get_file.
+#[test]
+fn test_embedded_index() {
+    let dir = embedded_dir();
+    let index = dir.index();
+    assert_eq!(index.len(), dir.walk().count());
+    for path in dir.paths() {
+        assert_eq!(index.get(&path), dir.get_file(&path).as_ref());
+    }
+    assert!(index.get("/subdir/gamma.txt").is_some());
+    assert!(index.get("notfound.txt").is_none());
+    for name in [
+        "override/../alpha.txt",
+        "./subdir//gamma.txt",
+        "../beta.txt",
+    ] {
+        assert_eq!(index.get(name), dir.get_file(name).as_ref(), "{name}");
+    }
+    let parent = index.get("override/../alpha.txt").unwrap();
+    assert_eq!(parent.path(), std::path::Path::new("alpha.txt"));
+    assert!(index.get("../alpha.txt").is_none());
+    let sub_index = dir.get_dir("subdir").unwrap().index();
+    assert_eq!(sub_index.get("subsubdir/zeta.txt").unwrap().file_name(), Some("zeta.txt"));
+}
+
+/// Checks that the strong etag of an embedded file matches its dynamic counterpart.
+#[test]
+fn test_embedded_etag_matches_dynamic() {
+    let embedded = embedded_dir().get_file("alpha.txt").unwrap();
+    let dynamic = embedded_dir().into_dynamic().get_file("alpha.txt").unwrap();
+    assert_eq!(embedded.etag().unwrap(), dynamic.etag().unwrap());
+}
+
+/// Checks that walk_sorted() yields the same depth-first sorted sequence in both modes.
+#[test]
+fn test_embedded_walk_sorted_matches_dynamic() {
+    let embedded: Vec<_> = embedded_dir().walk_sorted().map(|f| f.path().to_owned()).collect();
+    let dynamic: Vec<_> = embedded_dir().into_dynamic().walk_sorted().map(|f| f.path().to_owned()).collect();
+    assert_eq!(embedded, dynamic);
+    let mut sorted = embedded.clone();
+    sorted.sort();
+    assert_eq!(embedded, sorted);
+}
+
+/// Checks that embedded count_entries() agrees with the dynamic count.
+#[test]
+fn test_embedded_count_entries() {
+    assert_eq!(embedded_dir().count_entries(), embedded_dir().into_dynamic().count_entries());
+}
+
+/// Checks that embedded files expose their contents as static slices while dynamic ones do not.
+#[test]
+fn test_embedded_as_static_str_and_bytes() {
+    let file = embedded_dir().get_file("alpha.txt").unwrap();
+    let text: &'static str = file.as_static_str().unwrap();
+    assert_eq!(text.trim(), "Hello from alpha!");
+    assert_eq!(file.as_static_bytes().unwrap(), text.as_bytes());
+    let dynamic = embedded_dir().into_dynamic().get_file("alpha.txt").unwrap();
+    assert!(dynamic.as_static_str().is_none());
+    assert!(dynamic.as_static_bytes().is_none());
+}
+
+/// Checks that DirSet::into_dynamic() and auto_dynamic() convert every layer.
+#[test]
+fn test_embedded_dirset_into_dynamic() {
+    let set = DirSet::new(vec![embedded_dir(), embedded_dir().get_dir("override").unwrap()]);
+    assert!(set.dirs.iter().all(|d| d.is_embedded()));
+    let dynamic = set.clone().into_dynamic();
+    assert!(dynamic.dirs.iter().all(|d| !d.is_embedded()));
+    assert_eq!(dynamic.get_file("alpha.txt").unwrap().read_str().unwrap(), set.get_file("alpha.txt").unwrap().read_str().unwrap());
+    let auto = set.auto_dynamic();
+    assert_eq!(auto.dirs.iter().all(|d| !d.is_embedded()), cfg!(debug_assertions));
+}
+
+/// Checks that chunks() yields the same pieces for embedded and dynamic files.
+#[test]
+fn test_embedded_chunks_match_dynamic() {
+    let embedded = embedded_dir().get_file("subdir/gamma.txt").unwrap();
+    let dynamic = embedded_dir().into_dynamic().get_file("subdir/gamma.txt").unwrap();
+    let a: Vec<Vec<u8>> = embedded.chunks(4).map(|c| c.unwrap()).collect();
+    let b: Vec<Vec<u8>> = dynamic.chunks(4).map(|c| c.unwrap()).collect();
+    assert_eq!(a, b);
+    assert_eq!(a.concat(), embedded.read_bytes().unwrap());
+}
+
+/// Checks that the testing helpers report embedded/dynamic parity for every subtree.
+#[cfg(feature = "testing")]
+#[test]
+fn test_testing_both_modes() {
+    let (embedded, dynamic) = fs_embed::testing::both_modes(embedded_dir());
+    assert!(embedded.is_embedded() && !dynamic.is_embedded());
+    fs_embed::testing::assert_dirs_equivalent(&embedded, &dynamic);
+    fs_embed::testing::assert_dirs_equivalent(&embedded.get_dir("subdir").unwrap(), &dynamic.get_dir("subdir").unwrap());
+    let (embedded, dynamic) = fs_embed::testing::both_modes(MOUNTED.clone());
+    fs_embed::testing::assert_dirs_equivalent(&embedded, &dynamic);
+}
+
+/// Checks that a with_file() replacement applies to embedded trees and survives into_dynamic().
+#[test]
+fn test_embedded_with_file() {
+    let custom = embedded_dir().with_file("subdir/gamma.txt", b"replaced".to_vec());
+    assert!(custom.is_embedded());
+    let file = custom.get_file("subdir/gamma.txt").unwrap();
+    assert!(!file.is_embedded());
+    assert_eq!(file.read_str().unwrap(), "replaced");
+    let dynamic = custom.into_dynamic();
+    assert_eq!(dynamic.get_file("subdir/gamma.txt").unwrap().read_str().unwrap(), "replaced");
+    let walked = dynamic.walk().find(|f| f.path() == std::path::Path::new("subdir/gamma.txt")).unwrap();
+    assert_eq!(walked.read_str().unwrap(), "replaced");
+    assert!(dynamic.get_file("alpha.txt").unwrap().read_bytes().is_ok());
+}
+
+/// Checks that directories created by with_file() in an embedded tree are embedded too.
+#[test]
+fn test_embedded_with_file_new_dir() {
+    let custom = embedded_dir().with_file("fresh/new.txt", b"new".to_vec());
+    let fresh = custom.get_dir("fresh").unwrap();
+    assert!(fresh.is_embedded());
+    assert_eq!(fresh.path(), std::path::Path::new("fresh"));
+    assert_eq!(fresh.get_file("new.txt").unwrap().read_str().unwrap(), "new");
+    let listed = custom.entries().into_iter().find(|e| e.path() == std::path::Path::new("fresh"));
+    assert!(listed.unwrap().into_dir().unwrap().is_embedded());
+    let dynamic = custom.into_dynamic().get_dir("fresh").unwrap();
+    assert!(!dynamic.is_embedded());
+    assert_eq!(dynamic.get_file("new.txt").unwrap().read_str().unwrap(), "new");
+}
+
+/// Checks that walk() reports an exact size hint for embedded trees and a loose one for dynamic.
+#[test]
+fn test_embedded_walk_size_hint() {
+    let dir = embedded_dir();
+    let total = dir.walk().count();
+    let mut walk = dir.walk();
+    assert_eq!(walk.size_hint(), (total, Some(total)));
+    walk.next();
+    assert_eq!(walk.size_hint(), (total - 1, Some(total - 1)));
+    assert_eq!(embedded_dir().into_dynamic().walk().size_hint(), (0, None));
+    for depth in 0..4 {
+        let walk = dir.walk_max_depth(depth);
+        let expected = dir.walk_max_depth(depth).count();
+        assert_eq!(
+            walk.size_hint(),
+            (expected, Some(expected)),
+            "depth {depth}"
+        );
+    }
+}
+
+/// Checks that CachedDirSet hands out embedded contents in place, without caching them.
+#[test]
+fn test_embedded_cached_dir_set_borrows() {
+    let cache = CachedDirSet::new(embedded_dir().into_dir_set(), 1024);
+    let bytes = cache.read_bytes("alpha.txt").unwrap();
+    let file = embedded_dir().get_file("alpha.txt").unwrap();
+    assert!(matches!(bytes, CachedBytes::Static(_)));
+    assert_eq!(bytes.as_ptr(), file.as_static_bytes().unwrap().as_ptr());
+    assert_eq!(cache.cached_bytes(), 0);
+}
+
+/// Checks that read_bytes_shared() borrows embedded contents and reads dynamic ones.
+#[cfg(feature = "bytes")]
+#[test]
+fn test_embedded_read_bytes_shared() {
+    let file = embedded_dir().get_file("alpha.txt").unwrap();
+    let shared = file.read_bytes_shared().unwrap();
+    assert_eq!(shared.as_ptr(), file.as_static_bytes().unwrap().as_ptr());
+    let dynamic = embedded_dir().into_dynamic().get_file("alpha.txt").unwrap();
+    assert_eq!(dynamic.read_bytes_shared().unwrap(), shared);
+}
+
+/// Checks that diff_embedded_vs_disk() reports edited, deleted and added files on disk.
+#[test]
+fn test_embedded_diff_vs_disk() {
+    assert!(embedded_dir().diff_embedded_vs_disk().unwrap().is_empty());
+    assert!(embedded_dir().into_dynamic().diff_embedded_vs_disk().unwrap().is_empty());
+
+    let temp_dir = test_temp_dir("drift");
+    for file in embedded_dir().walk() {
+        let target = temp_dir.path().join(file.path());
+        std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+        std::fs::write(target, file.read_bytes().unwrap()).unwrap();
+    }
+    std::fs::write(temp_dir.path().join("alpha.txt"), b"edited").unwrap();
+    std::fs::remove_file(temp_dir.path().join("subdir/gamma.txt")).unwrap();
+    std::fs::write(temp_dir.path().join("new.txt"), b"new").unwrap();
+
+    let root: &'static str = Box::leak(temp_dir.path().to_str().unwrap().to_string().into_boxed_str());
+    let dir = Dir::from_embedded(RAW.clone(), root);
+    let drifted = dir.diff_embedded_vs_disk().unwrap();
+    let expected: Vec<std::path::PathBuf> = ["alpha.txt", "new.txt", "subdir/gamma.txt"].iter().map(std::path::PathBuf::from).collect();
+    assert_eq!(drifted, expected);
+}
+
+/// Checks that embedded get_dir() resolves names with leading and trailing slashes.
+#[test]
+fn test_embedded_get_dir_slashes() {
+    let dir = embedded_dir();
+    assert_eq!(dir.get_dir("/subdir/").unwrap().path(), dir.get_dir("subdir").unwrap().path());
+    assert!(dir.get_dir("subdir/subsubdir/").is_some());
+}
+
+/// Checks that siblings() agrees between embedded and dynamic files, including from the root.
+#[test]
+fn test_embedded_siblings_match_dynamic() {
+    let names = |file: fs_embed::File| file.siblings().unwrap().iter().map(|f| f.rel_url()).collect::<Vec<_>>();
+    let embedded = embedded_dir().get_file("subdir/gamma.txt").unwrap();
+    let dynamic = embedded_dir().into_dynamic().get_file("subdir/gamma.txt").unwrap();
+    assert_eq!(names(embedded), names(dynamic));
+    let alpha = embedded_dir().get_file("alpha.txt").unwrap();
+    assert_eq!(names(alpha), vec!["beta.txt".to_string()]);
+}
+
+/// Checks that from_env_or_embedded() prefers an existing directory from the environment.
+#[test]
+fn test_from_env_or_embedded() {
+    const KEY: &str = "FS_EMBED_TEST_FROM_ENV_OR_EMBEDDED";
+    let dir = Dir::from_env_or_embedded(KEY, embedded_dir());
+    assert!(dir.is_embedded());
+
+    let temp_dir = test_temp_dir("env");
+    std::fs::write(temp_dir.path().join("only_on_disk.txt"), b"disk").unwrap();
+    // SAFETY: no other test in this binary reads or writes the environment.
+    unsafe { std::env::set_var(KEY, temp_dir.path()) };
+    let dir = Dir::from_env_or_embedded(KEY, embedded_dir());
+    assert!(!dir.is_embedded());
+    assert_eq!(dir.origin(), fs_embed::Origin::Filesystem);
+    assert!(dir.get_file("only_on_disk.txt").is_some());
+
+    // SAFETY: as above.
+    unsafe { std::env::set_var(KEY, temp_dir.path().join("missing")) };
+    assert!(Dir::from_env_or_embedded(KEY, embedded_dir()).is_embedded());
+    // SAFETY: as above.
+    unsafe { std::env::remove_var(KEY) };
+}
+
+/// Checks that directory paths are rejected the same way by embedded and dynamic backends.
+#[test]
+fn test_read_bytes_directory_guard() {
+    for dir in [embedded_dir(), embedded_dir().into_dynamic()] {
+        assert!(dir.get_file("subdir").is_none());
+        assert!(dir.get_file("subdir/").is_none());
+        let err = dir.read_bytes("subdir").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        let err = dir.read_bytes("missing.txt").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(
+            dir.read_bytes("alpha.txt").unwrap(),
+            dir.get_file("alpha.txt").unwrap().read_bytes().unwrap()
+        );
+    }
+}
+
+/// Checks that with_disk_overlay() serves patched files from disk and the rest from the embed.
+#[test]
+fn test_with_disk_overlay() {
+    let temp_dir = test_temp_dir("disk_overlay");
+    std::fs::create_dir_all(temp_dir.path().join("subdir")).unwrap();
+    std::fs::write(temp_dir.path().join("subdir/gamma.txt"), b"patched").unwrap();
+    std::fs::write(temp_dir.path().join("subdir/extra.txt"), b"extra").unwrap();
+
+    let dir = embedded_dir().with_disk_overlay(temp_dir.path());
+    let gamma = dir.get_file("subdir/gamma.txt").unwrap();
+    assert!(!gamma.is_embedded());
+    assert_eq!(gamma.read_bytes().unwrap(), b"patched");
+    let alpha = dir.get_file("alpha.txt").unwrap();
+    assert!(alpha.is_embedded());
+
+    let subdir = dir.get_dir("subdir").unwrap();
+    assert_eq!(
+        subdir.get_file("gamma.txt").unwrap().read_bytes().unwrap(),
+        b"patched"
+    );
+    assert!(subdir.get_file("extra.txt").is_some());
+    assert!(
+        !subdir
+            .get_file("delta.txt")
+            .unwrap()
+            .read_bytes()
+            .unwrap()
+            .is_empty()
+    );
+
+    let mut expected = embedded_dir().paths();
+    expected.push("subdir/extra.txt".to_string());
+    expected.sort();
+    assert_eq!(dir.paths(), expected);
+    let walked = dir
+        .walk()
+        .find(|f| f.rel_url() == "subdir/gamma.txt")
+        .unwrap();
+    assert_eq!(walked.read_bytes().unwrap(), b"patched");
+}
+
+/// Checks that embedded_metadata() exposes the build-time timestamps for embedded files only.
+#[test]
+fn test_embedded_metadata() {
+    let file = embedded_dir().get_file("alpha.txt").unwrap();
+    let raw = file.embedded_metadata().expect("embedded metadata");
+    let modified = file.metadata().unwrap().modified;
+    assert_eq!(
+        std::time::UNIX_EPOCH
+            + raw
+                .modified()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap(),
+        modified
+    );
+    let dynamic = embedded_dir().into_dynamic().get_file("alpha.txt").unwrap();
+    assert!(dynamic.embedded_metadata().is_none());
+}
+
+/// Checks that walk_paths() pairs relative paths with on-disk paths only in dynamic mode.
+#[test]
+fn test_walk_paths() {
+    let embedded: Vec<_> = embedded_dir().walk_paths().collect();
+    assert_eq!(embedded.len(), embedded_dir().walk().count());
+    assert!(embedded.iter().all(|(_, absolute)| absolute.is_none()));
+
+    for (rel, absolute) in embedded_dir().into_dynamic().walk_paths() {
+        let absolute = absolute.expect("dynamic files have an absolute path");
+        assert!(absolute.is_file());
+        assert!(absolute.ends_with(&rel));
+    }
+}
+
+/// Checks that cached UTF-8 validation gives the same text on repeated embedded reads.
+#[test]
+fn test_read_str_cached_validation() {
+    let file = embedded_dir().get_file("alpha.txt").unwrap();
+    let text = file.as_static_str().expect("alpha.txt is UTF-8");
+    assert_eq!(text.as_ptr(), file.as_static_bytes().unwrap().as_ptr());
+    assert_eq!(file.as_static_str(), Some(text));
+    assert_eq!(file.read_str().unwrap(), text);
+    assert_eq!(file.read_str().unwrap(), text);
+}
+
+/// Checks that tree_hash() is backend-independent and changes with any file's contents.
+#[test]
+fn test_tree_hash() {
+    let hash = embedded_dir().tree_hash().unwrap();
+    assert_eq!(hash.len(), 64);
+    assert_eq!(embedded_dir().into_dynamic().tree_hash().unwrap(), hash);
+    assert_eq!(
+        embedded_dir().into_dir_set().tree_hash_override().unwrap(),
+        hash
+    );
+    let patched = embedded_dir().with_file("alpha.txt", b"changed".to_vec());
+    assert_ne!(patched.tree_hash().unwrap(), hash);
+
+    let subdir = embedded_dir().get_dir("subdir").unwrap();
+    assert_eq!(
+        subdir.tree_hash().unwrap(),
+        subdir.into_dynamic().tree_hash().unwrap()
+    );
+}
+
+/// Checks that size_report() takes embedded sizes from the contents, even without metadata.
+#[test]
+fn test_embedded_size_report_without_metadata() {
+    let bare = fs_embed!("tests/data", metadata = false);
+    assert!(bare.get_file("alpha.txt").unwrap().metadata().is_err());
+    let report = bare.size_report();
+    assert!(report.iter().all(|(_, size)| *size > 0));
+    assert_eq!(report, embedded_dir().into_dynamic().size_report());
+}
+
+/// Checks that source_location() is the crate-relative path of the embedded file's source.
+#[test]
+fn test_source_location() {
+    let file = embedded_dir().get_file("subdir/gamma.txt").unwrap();
+    let source = file
+        .source_location()
+        .expect("embedded files know their source");
+    assert_eq!(source, "tests/data/subdir/gamma.txt");
+    let on_disk = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(source);
+    assert_eq!(std::fs::read(on_disk).unwrap(), file.read_bytes().unwrap());
+    let mounted = MOUNTED.get_file("assets/data/subdir/gamma.txt").unwrap();
+    assert_eq!(mounted.source_location(), Some("tests/data/subdir/gamma.txt"));
+    let dynamic = embedded_dir().into_dynamic().get_file("alpha.txt").unwrap();
+    assert!(dynamic.source_location().is_none());
+}
+
+/// Checks that canonical_path() of an embedded directory is its relative path.
+#[test]
+fn test_embedded_canonical_path() {
+    let subdir = embedded_dir().get_dir("subdir").unwrap();
+    assert_eq!(subdir.canonical_path().unwrap(), subdir.path());
+}
+
+/// Checks that http::respond() serves files with validators and honours conditional requests.
+#[cfg(feature = "http")]
+#[test]
+fn test_http_respond() {
+    use ::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+    use fs_embed::http::respond;
+
+    for set in [
+        embedded_dir().into_dir_set(),
+        embedded_dir().into_dynamic().into_dir_set(),
+    ] {
+        let empty = HeaderMap::new();
+        let response = respond(&set, &Method::GET, "/alpha.txt", &empty);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+        let etag = response.headers()[header::ETAG].clone();
+        let last_modified = response.headers()[header::LAST_MODIFIED].clone();
+        let expected = set.get_file("alpha.txt").unwrap().read_bytes().unwrap();
+        assert_eq!(response.into_body().into_bytes().unwrap(), expected);
+
+        let head = respond(&set, &Method::HEAD, "/alpha.txt", &empty);
+        assert_eq!(head.status(), StatusCode::OK);
+        assert!(head.into_body().into_bytes().unwrap().is_empty());
+
+        let mut conditional = HeaderMap::new();
+        conditional.insert(header::IF_NONE_MATCH, etag);
+        let response = respond(&set, &Method::GET, "/alpha.txt", &conditional);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let mut conditional = HeaderMap::new();
+        conditional.insert(header::IF_MODIFIED_SINCE, last_modified);
+        let response = respond(&set, &Method::GET, "/alpha.txt", &conditional);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let mut stale = HeaderMap::new();
+        stale.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        let response = respond(&set, &Method::GET, "/alpha.txt", &stale);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = respond(&set, &Method::GET, "/missing.txt", &empty);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = respond(&set, &Method::POST, "/alpha.txt", &empty);
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET, HEAD");
+    }
+}
+
+/// Checks that http::respond_with() takes the content type from the overrides first.
+#[cfg(feature = "http")]
+#[test]
+fn test_http_respond_with_overrides() {
+    use ::http::{HeaderMap, Method, header};
+
+    static OVERRIDES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+        "txt" => "text/plain; charset=utf-8",
+    };
+    let set = embedded_dir().into_dir_set();
+    let empty = HeaderMap::new();
+    let response =
+        fs_embed::http::respond_with(&set, &Method::GET, "/alpha.txt", &empty, &OVERRIDES);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/plain; charset=utf-8"
+    );
+    let etag = response.headers()[header::ETAG].clone();
+    let again = fs_embed::http::respond(&set, &Method::GET, "/alpha.txt", &empty);
+    assert_eq!(again.headers()[header::CONTENT_TYPE], "text/plain");
+    assert_eq!(again.headers()[header::ETAG], etag);
+    assert_eq!(etag, set.get_file("alpha.txt").unwrap().etag().unwrap());
+}
+
+/// Checks that http::respond() refuses request paths that would leave the served directory.
+#[cfg(feature = "http")]
+#[test]
+fn test_http_respond_rejects_traversal() {
+    use ::http::{HeaderMap, Method, StatusCode};
+    use fs_embed::http::respond;
+
+    let manifest = env!("CARGO_MANIFEST_DIR").trim_start_matches('/');
+    let attacks = [
+        format!("//{manifest}/Cargo.toml"),
+        format!("/{}{manifest}/Cargo.toml", "../".repeat(16)),
+        "/../../Cargo.toml".to_string(),
+        "%2e%2e/%2e%2e/Cargo.toml".to_string(),
+        "/%2e%2e%2f%2e%2e%2fCargo.toml".to_string(),
+        "/subdir/%2e%2e/%2e%2e/%2e%2e/Cargo.toml".to_string(),
+        "/..%5c..%5cCargo.toml".to_string(),
+        "/./alpha.txt".to_string(),
+        "/subdir//gamma.txt".to_string(),
+    ];
+    for set in [
+        embedded_dir().into_dir_set(),
+        embedded_dir().into_dynamic().into_dir_set(),
+    ] {
+        for attack in &attacks {
+            let response = respond(&set, &Method::GET, attack, &HeaderMap::new());
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{attack}");
+        }
+        let response = respond(&set, &Method::GET, "/subdir/gamma.txt", &HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -49,6 +49,14 @@ fn test_override_dir() -> Dir {
     Dir::from_str("tests/data/override")
 }
 
+/// Creates a fresh temporary directory named after the calling test.
+fn test_temp_dir(name: &str) -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix(&format!("fs_embed_test_{name}_"))
+        .tempdir()
+        .expect("create temp dir")
+}
+
 /// Checks that directory entries include expected files and subdirectories.
 #[test]
 fn test_dir_entries() {
@@ -352,10 +360,7 @@ fn test_file_read_str_invalid_utf8() {
     use std::fs;
     // unused std::path::Path import removed
     // Create a unique temp directory for this test
-    let temp_dir = tempfile::Builder::new()
-        .prefix("fs_embed_test_bad_utf8_")
-        .tempdir()
-        .expect("create temp dir");
+    let temp_dir = test_temp_dir("bad_utf8");
     let file_path = temp_dir.path().join("bad_utf8.bin");
     let mut f = fs::File::create(&file_path).unwrap();
     f.write_all(&[0xff, 0xfe, 0xfd]).unwrap();
@@ -390,3 +395,1171 @@ fn test_direntry_clone_hash_eq() {
         assert_eq!(entry, entry.clone());
     }
 }
+
+/// Checks that write_tar() streams every file with its relative path and contents.
+#[cfg(feature = "tar")]
+#[test]
+fn test_dir_write_tar() {
+    use std::io::Read;
+    let dir = test_dir();
+    let mut buf = Vec::new();
+    dir.write_tar(&mut buf).unwrap();
+    let mut archive = tar::Archive::new(buf.as_slice());
+    let mut names = Vec::new();
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().into_owned();
+        let mut content = String::new();
+        entry.read_to_string(&mut content).unwrap();
+        if path == std::path::Path::new("alpha.txt") {
+            assert_eq!(content.trim(), "Hello from alpha!");
+        }
+        names.push(path);
+    }
+    assert_eq!(names.len(), dir.walk().count());
+    assert!(names.contains(&std::path::PathBuf::from("subdir/gamma.txt")));
+}
+
+/// Checks that write_tar() falls back to mtime 0 for embedded files without metadata.
+#[cfg(feature = "tar")]
+#[test]
+fn test_dir_write_tar_without_metadata() {
+    static FILES: &[include_dir::DirEntry<'static>] = &[include_dir::DirEntry::File(
+        include_dir::File::new("bare.txt", b"bare"),
+    )];
+    let dir = Dir::from_embedded(include_dir::Dir::new("", FILES), "");
+    assert!(dir.get_file("bare.txt").unwrap().metadata().is_err());
+    let mut buf = Vec::new();
+    dir.write_tar(&mut buf).unwrap();
+    let mut archive = tar::Archive::new(buf.as_slice());
+    let entry = archive.entries().unwrap().next().unwrap().unwrap();
+    assert_eq!(entry.header().mtime().unwrap(), 0);
+    assert_eq!(entry.header().size().unwrap(), 4);
+}
+
+/// Checks that http::respond() serves embedded files without metadata instead of failing.
+#[cfg(feature = "http")]
+#[test]
+fn test_http_respond_without_metadata() {
+    use ::http::{HeaderMap, Method, StatusCode, header};
+
+    static FILES: &[include_dir::DirEntry<'static>] = &[include_dir::DirEntry::File(
+        include_dir::File::new("bare.txt", b"bare"),
+    )];
+    let set = Dir::from_embedded(include_dir::Dir::new("", FILES), "").into_dir_set();
+    let response = fs_embed::http::respond(&set, &Method::GET, "/bare.txt", &HeaderMap::new());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_LENGTH], "4");
+    assert_eq!(
+        response.headers()[header::LAST_MODIFIED],
+        "Thu, 01 Jan 1970 00:00:00 GMT"
+    );
+    assert_eq!(response.into_body().into_bytes().unwrap(), b"bare");
+}
+
+/// Checks that walk_under() only yields files below the given prefix.
+#[test]
+fn test_walk_under() {
+    let dir = test_dir();
+    let names: Vec<_> = dir.walk_under("subdir").map(|f| f.path().to_owned()).collect();
+    assert!(names.contains(&std::path::PathBuf::from("subdir/gamma.txt")));
+    assert!(names.contains(&std::path::PathBuf::from("subdir/subsubdir/zeta.txt")));
+    assert!(names.iter().all(|p| p.starts_with("subdir")));
+    assert_eq!(dir.walk_under("").count(), dir.walk().count());
+    assert_eq!(dir.walk_under("missing").count(), 0);
+}
+
+/// Checks that duplicate_paths() is empty for a clean tree and that case-folding detects collisions.
+#[test]
+fn test_duplicate_paths() {
+    assert!(test_dir().duplicate_paths().is_empty());
+
+    let temp_dir = test_temp_dir("duplicates");
+    std::fs::write(temp_dir.path().join("Logo.png"), b"a").unwrap();
+    std::fs::write(temp_dir.path().join("logo.png"), b"b").unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    assert!(dir.duplicate_paths().is_empty());
+    assert_eq!(dir.duplicate_paths_ignore_case(), vec![std::path::PathBuf::from("logo.png")]);
+}
+
+/// Checks that suggest() ranks the closest existing paths first.
+#[test]
+fn test_dir_suggest() {
+    let dir = test_dir();
+    let suggestions = dir.suggest("alpah.txt", 2);
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0], "alpha.txt");
+    let nested = dir.get_dir("subdir").unwrap().suggest("gama.txt", 1);
+    assert_eq!(nested, vec!["gamma.txt".to_string()]);
+    assert!(dir.suggest("alpha.txt", 0).is_empty());
+}
+
+/// Checks that CachedDirSet serves cached bytes, re-reads edited files and respects its budget.
+#[test]
+fn test_cached_dir_set() {
+    let temp_dir = test_temp_dir("cache");
+    std::fs::write(temp_dir.path().join("a.txt"), b"first").unwrap();
+    std::fs::write(temp_dir.path().join("b.txt"), b"second").unwrap();
+    let cache = CachedDirSet::new(DirSet::new(vec![Dir::from_path(temp_dir.path())]), 8);
+
+    assert_eq!(&*cache.read_bytes("a.txt").unwrap(), b"first");
+    assert_eq!(cache.cached_bytes(), 5);
+    assert_eq!(&*cache.read_bytes("b.txt").unwrap(), b"second");
+    assert_eq!(cache.cached_bytes(), 6, "least recently used entry should be evicted");
+
+    std::fs::write(temp_dir.path().join("b.txt"), b"edited!").unwrap();
+    assert_eq!(&*cache.read_bytes("b.txt").unwrap(), b"edited!");
+
+    let err = cache.read_bytes("missing.txt").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    cache.clear();
+    assert_eq!(cache.cached_bytes(), 0);
+}
+
+/// Checks that walk_pruned() skips subdirectories rejected by the predicate.
+#[test]
+fn test_walk_pruned() {
+    let dir = test_dir();
+    let files: Vec<_> = dir.walk_pruned(|d| d.path() != std::path::Path::new("subdir")).collect();
+    assert!(files.iter().any(|f| f.file_name() == Some("alpha.txt")));
+    assert!(files.iter().any(|f| f.file_name() == Some("epsilon.txt")));
+    assert!(files.iter().all(|f| !f.path().starts_with("subdir")));
+    assert_eq!(dir.walk_pruned(|_| true).count(), dir.walk().count());
+}
+
+/// Checks that same_root() distinguishes layers that compare equal by relative path.
+#[test]
+fn test_dir_same_root_and_dedup() {
+    let base = test_dir();
+    let over = test_override_dir();
+    assert_eq!(base, over, "root dirs compare equal by relative path");
+    assert!(!base.same_root(&over));
+    assert!(base.same_root(&test_dir()));
+
+    let mut set = DirSet::new(vec![test_dir(), test_override_dir(), test_dir()]);
+    set.dedup_roots();
+    assert_eq!(set.dirs.len(), 2);
+    assert!(set.dirs[0].same_root(&test_override_dir()));
+    assert!(set.dirs[1].same_root(&test_dir()));
+}
+
+/// Checks that dedup_roots() keeps distinct overlay and filter views of one base.
+#[test]
+fn test_dedup_roots_keeps_distinct_views() {
+    let base = test_dir();
+    let with_a = base.with_file("a.txt", b"a".to_vec());
+    let with_b = base.with_file("b.txt", b"b".to_vec());
+    let txt = base.filter(|f| f.extension() == Some("txt"));
+    let none = base.filter(|_| false);
+    assert!(!with_a.same_root(&with_b));
+    assert!(!with_a.same_root(&base));
+    assert!(!txt.same_root(&none));
+    assert!(with_a.same_root(&with_a.clone()));
+
+    let views = vec![with_a.clone(), with_b, txt.clone(), none, base, with_a, txt];
+    let mut set = DirSet::new(views);
+    set.dedup_roots();
+    assert_eq!(set.dirs.len(), 5);
+    assert!(set.get_file("a.txt").is_some());
+    assert!(set.get_file("b.txt").is_some());
+}
+
+/// Checks that Dir and DirSet can be used through the generic Source trait.
+#[test]
+fn test_source_trait_generic() {
+    fn read_alpha<S: Source>(source: &S) -> String {
+        source.get_file("alpha.txt").unwrap().read_str().unwrap()
+    }
+    fn count<S: Source>(source: &S) -> usize {
+        source.walk().filter(|f| !f.is_embedded()).count()
+    }
+    let dir = test_dir();
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    assert_eq!(read_alpha(&dir).trim(), "Hello from alpha!");
+    assert_eq!(read_alpha(&set).trim(), "Overridden alpha!");
+    assert_eq!(count(&dir), dir.walk().count());
+    assert_eq!(count(&set), set.walk_override().count());
+}
+
+/// Checks that paths() and paths_override() return sorted forward-slash relative paths.
+#[test]
+fn test_paths_and_paths_override() {
+    let paths = test_dir().paths();
+    assert!(paths.contains(&"subdir/subsubdir/zeta.txt".to_string()));
+    assert!(paths.windows(2).all(|w| w[0] <= w[1]));
+
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let resolved = set.paths_override();
+    assert_eq!(resolved.iter().filter(|p| p.as_str() == "alpha.txt").count(), 1);
+    assert!(resolved.contains(&"epsilon.txt".to_string()));
+    assert_eq!(set.get_file("subdir/gamma.txt").unwrap().rel_url(), "subdir/gamma.txt");
+}
+
+/// Checks that walk_entries_keep_empty() yields empty directories alongside files.
+#[test]
+fn test_walk_entries_keep_empty() {
+    let temp_dir = test_temp_dir("empty");
+    std::fs::create_dir_all(temp_dir.path().join("scaffold/empty")).unwrap();
+    std::fs::write(temp_dir.path().join("readme.md"), b"hi").unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    assert_eq!(dir.walk().count(), 1);
+    let entries: Vec<_> = dir.walk_entries_keep_empty().collect();
+    assert_eq!(entries.len(), 3);
+    assert!(entries.iter().any(|e| e.is_dir() && e.path() == std::path::Path::new("scaffold/empty")));
+    assert!(entries.iter().any(|e| e.is_file() && e.path() == std::path::Path::new("readme.md")));
+}
+
+/// Checks that read_str_prefix() trims to a character boundary instead of failing.
+#[test]
+fn test_file_read_str_prefix() {
+    let temp_dir = test_temp_dir("prefix");
+    std::fs::write(temp_dir.path().join("text.txt"), "añb".as_bytes()).unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    let file = dir.get_file("text.txt").unwrap();
+    assert_eq!(file.read_str_prefix(1).unwrap(), "a");
+    assert_eq!(file.read_str_prefix(2).unwrap(), "a", "split 'ñ' must be dropped");
+    assert_eq!(file.read_str_prefix(3).unwrap(), "añ");
+    assert_eq!(file.read_str_prefix(100).unwrap(), "añb");
+
+    std::fs::write(temp_dir.path().join("bad.bin"), [b'a', 0xff, b'b']).unwrap();
+    assert!(dir.get_file("bad.bin").unwrap().read_str_prefix(3).is_err());
+}
+
+/// Checks that get_file_origin() reports the index of the winning layer.
+#[test]
+fn test_dirset_get_file_origin() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let (layer, file) = set.get_file_origin("alpha.txt").unwrap();
+    assert_eq!(layer, 1);
+    assert_eq!(file.read_str().unwrap().trim(), "Overridden alpha!");
+    assert_eq!(set.get_file_origin("beta.txt").unwrap().0, 0);
+    assert!(set.get_file_origin("notfound.txt").is_none());
+}
+
+/// Checks that weak_etag() is a weak validator and etag() a strong content hash.
+#[test]
+fn test_file_etags() {
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    let weak = file.weak_etag().unwrap();
+    assert!(weak.starts_with("W/\"") && weak.ends_with('"'));
+    assert_eq!(weak, file.weak_etag().unwrap());
+    let strong = file.etag().unwrap();
+    assert_eq!(strong.len(), 64 + 2);
+    assert!(strong.starts_with('"'));
+    let other = test_dir().get_file("beta.txt").unwrap();
+    assert_ne!(strong, other.etag().unwrap());
+}
+
+/// Checks that name() returns the basename for entries and directories.
+#[test]
+fn test_direntry_and_dir_name() {
+    let dir = test_dir();
+    let names: Vec<_> = dir.entries().iter().filter_map(|e| e.name().map(str::to_string)).collect();
+    assert!(names.contains(&"alpha.txt".to_string()));
+    assert!(names.contains(&"subdir".to_string()));
+    assert_eq!(dir.name(), None);
+    assert_eq!(dir.get_dir("subdir/subsubdir").unwrap().name(), Some("subsubdir"));
+}
+
+/// Checks that count_entries() matches a full walk and that the override count dedups paths.
+#[test]
+fn test_count_entries() {
+    let dir = test_dir();
+    let (dirs, files) = dir.count_entries();
+    assert_eq!(files, dir.walk().count());
+    assert_eq!(dirs, dir.walk_entries_keep_empty().filter(|e| e.is_dir()).count());
+    assert_eq!(dirs, 3);
+
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let (_, override_files) = set.count_entries_override();
+    assert_eq!(override_files, set.walk_override().count());
+}
+
+/// Checks that read errors on dynamic files name the offending path and keep their kind.
+#[test]
+fn test_read_error_includes_path() {
+    let temp_dir = test_temp_dir("read_error");
+    std::fs::write(temp_dir.path().join("gone.txt"), b"soon").unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    let file = dir.get_file("gone.txt").unwrap();
+    std::fs::remove_file(temp_dir.path().join("gone.txt")).unwrap();
+
+    let err = file.read_bytes().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("gone.txt"));
+    let path_err = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<fs_embed::PathError>())
+        .expect("wrapped in PathError");
+    assert_eq!(path_err.relative_path(), std::path::Path::new("gone.txt"));
+    assert_eq!(path_err.absolute_path(), file.absolute_path());
+    assert_eq!(file.read_str().unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+/// Checks that walk_excluding() skips matching files and prunes excluded directories.
+#[cfg(feature = "glob")]
+#[test]
+fn test_walk_excluding() {
+    let dir = test_dir();
+    let paths: Vec<String> = dir
+        .walk_excluding(&["subdir/**", "*/epsilon.txt"])
+        .unwrap()
+        .map(|f| f.rel_url())
+        .collect();
+    assert!(paths.contains(&"alpha.txt".to_string()));
+    assert!(paths.contains(&"override/alpha.txt".to_string()));
+    assert!(!paths.iter().any(|p| p.starts_with("subdir/")));
+    assert!(!paths.contains(&"override/epsilon.txt".to_string()));
+}
+
+/// Checks that walk_excluding() reports an invalid pattern instead of panicking.
+#[cfg(feature = "glob")]
+#[test]
+fn test_walk_excluding_invalid_pattern() {
+    let dir = test_dir();
+    assert!(dir.walk_excluding(&["a/{b"]).is_err());
+}
+
+/// Checks that common_ancestor() compares whole components and handles empty input.
+#[test]
+fn test_common_ancestor() {
+    let dir = test_dir();
+    let gamma = dir.get_file("subdir/gamma.txt").unwrap();
+    let zeta = dir.get_file("subdir/subsubdir/zeta.txt").unwrap();
+    let alpha = dir.get_file("alpha.txt").unwrap();
+    assert_eq!(fs_embed::common_ancestor(&[]), None);
+    assert_eq!(fs_embed::common_ancestor(std::slice::from_ref(&zeta)), Some(std::path::PathBuf::from("subdir/subsubdir")));
+    assert_eq!(fs_embed::common_ancestor(&[gamma, zeta.clone()]), Some(std::path::PathBuf::from("subdir")));
+    assert_eq!(fs_embed::common_ancestor(&[zeta, alpha]), Some(std::path::PathBuf::new()));
+}
+
+/// Checks that read_str_normalized() converts CRLF line endings to LF.
+#[test]
+fn test_read_str_normalized() {
+    let temp_dir = test_temp_dir("crlf");
+    std::fs::write(temp_dir.path().join("crlf.txt"), b"a\r\nb\rc\r\n").unwrap();
+    let file = Dir::from_path(temp_dir.path()).get_file("crlf.txt").unwrap();
+    assert_eq!(file.read_str().unwrap(), "a\r\nb\rc\r\n");
+    assert_eq!(file.read_str_normalized().unwrap(), "a\nb\rc\n");
+}
+
+/// Checks that latest_modified() returns the newest mtime across the override walk.
+#[test]
+fn test_dirset_latest_modified() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let latest = set.latest_modified().unwrap();
+    let newest = set.walk_override().map(|f| f.metadata().unwrap().modified).max().unwrap();
+    assert_eq!(latest, newest);
+    assert_eq!(DirSet::new(vec![]).latest_modified().unwrap(), std::time::UNIX_EPOCH);
+}
+
+/// Checks that chunks() splits a file into fixed-size pieces with a shorter tail.
+#[test]
+fn test_file_chunks() {
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    let contents = file.read_bytes().unwrap();
+    let chunks: Vec<Vec<u8>> = file.chunks(3).map(|c| c.unwrap()).collect();
+    assert_eq!(chunks.len(), contents.len().div_ceil(3));
+    assert!(chunks.iter().all(|c| !c.is_empty() && c.len() <= 3));
+    assert_eq!(chunks.concat(), contents);
+}
+
+/// Checks that with_file() replaces or adds a single file while everything else falls through.
+#[test]
+fn test_dir_with_file() {
+    let dir = test_dir();
+    let custom = dir
+        .with_file("alpha.txt", b"custom".to_vec())
+        .with_file("subdir/extra/new.txt", b"new".to_vec());
+    assert_eq!(custom.get_file("alpha.txt").unwrap().read_str().unwrap(), "custom");
+    assert_eq!(custom.get_file("beta.txt").unwrap().read_bytes().unwrap(), dir.get_file("beta.txt").unwrap().read_bytes().unwrap());
+    assert_ne!(dir.get_file("alpha.txt").unwrap().read_str().unwrap(), "custom");
+
+    let extra = custom.get_dir("subdir").unwrap().get_dir("extra").unwrap();
+    assert_eq!(extra.path(), std::path::Path::new("subdir/extra"));
+    assert_eq!(extra.get_file("new.txt").unwrap().read_str().unwrap(), "new");
+
+    let mut paths = custom.paths();
+    let mut expected = dir.paths();
+    expected.push("subdir/extra/new.txt".to_string());
+    paths.sort();
+    expected.sort();
+    assert_eq!(paths, expected);
+    assert_eq!(custom.count_entries(), (dir.count_entries().0 + 1, dir.count_entries().1 + 1));
+}
+
+/// Checks that with_file() resolves `..` like get_file() and that many additions stay visible.
+#[test]
+fn test_dir_with_file_names() {
+    let dir = test_dir().with_file("subdir/../alpha.txt", b"custom".to_vec());
+    assert_eq!(dir.get_file("alpha.txt").unwrap().read_str().unwrap(), "custom");
+    assert!(dir.get_dir("subdir").unwrap().get_file("alpha.txt").is_none());
+
+    let mut many = test_dir();
+    for i in 0..100 {
+        many = many.with_file(&format!("gen/{}.txt", i % 40), format!("{i}").into_bytes());
+    }
+    assert_eq!(many.get_dir("gen").unwrap().entries().len(), 40);
+    assert_eq!(many.get_file("gen/3.txt").unwrap().read_str().unwrap(), "83");
+    assert_eq!(many.walk().count(), test_dir().walk().count() + 40);
+}
+
+/// Checks that with_file() refuses a path that leaves the directory.
+#[test]
+#[should_panic(expected = "not a file below the directory")]
+fn test_dir_with_file_outside_root() {
+    test_dir().get_dir("subdir").unwrap().with_file("../alpha.txt", Vec::new());
+}
+
+/// Checks that symlink_target() reports the link target and None for regular files.
+#[cfg(unix)]
+#[test]
+fn test_file_symlink_target() {
+    let temp_dir = test_temp_dir("symlink");
+    let target = temp_dir.path().join("real.txt");
+    std::fs::write(&target, b"real").unwrap();
+    std::os::unix::fs::symlink(&target, temp_dir.path().join("link.txt")).unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    let link = dir.get_file("link.txt").unwrap();
+    assert_eq!(link.symlink_target().unwrap(), Some(target));
+    assert_eq!(link.read_str().unwrap(), "real");
+    assert_eq!(dir.get_file("real.txt").unwrap().symlink_target().unwrap(), None);
+}
+
+/// Checks that warm() visits each file of the override walk exactly once.
+#[test]
+fn test_dirset_warm() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    assert_eq!(set.warm().unwrap(), set.walk_override().count());
+}
+
+/// Checks that get_file() ignores a leading slash and get_file_url() percent-decodes the path.
+#[test]
+fn test_get_file_leading_slash_and_url() {
+    let dir = test_dir();
+    assert_eq!(dir.get_file("/subdir/gamma.txt").unwrap().path(), dir.get_file("subdir/gamma.txt").unwrap().path());
+    assert!(dir.get_file_url("/subdir%2Fgamma.txt").is_some());
+    assert!(dir.get_file_url("/%61lpha.txt").is_some());
+    assert!(dir.get_file_url("/%FF.txt").is_none());
+
+    let temp_dir = test_temp_dir("url");
+    std::fs::write(temp_dir.path().join("my logo.png"), b"png").unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    assert!(dir.get_file_url("/my%20logo.png").is_some());
+}
+
+/// Checks that lookups normalize the name and never resolve outside the directory.
+#[test]
+fn test_get_file_stays_inside_root() {
+    let dir = test_dir();
+    let subdir = dir.get_dir("subdir").unwrap();
+    assert!(dir.get_file("//alpha.txt").is_some());
+    assert!(dir.get_file("subdir/./../alpha.txt").is_some());
+    assert!(subdir.get_file("../alpha.txt").is_none());
+    assert!(subdir.get_dir("..").is_none());
+    assert!(dir.get_file("../../Cargo.toml").is_none());
+    assert!(dir.get_file("/../../Cargo.toml").is_none());
+    assert!(dir.get_file_url("%2e%2e/%2e%2e/Cargo.toml").is_none());
+    assert!(dir.get_dir("../..").is_none());
+
+    let absolute = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    assert!(dir.get_file(absolute.to_str().unwrap()).is_none());
+    assert!(dir.get_file(&format!("/{}", absolute.display())).is_none());
+}
+
+/// Checks that cloning a dynamic or overlay Dir shares its paths instead of copying them.
+#[test]
+fn test_dir_clone_is_shared() {
+    let dir = test_dir().get_dir("subdir").unwrap();
+    let clone = dir.clone();
+    assert!(std::ptr::eq(dir.absolute_path(), clone.absolute_path()));
+    let overlay = dir.with_file("gamma.txt", b"x".to_vec());
+    assert!(std::ptr::eq(overlay.absolute_path(), overlay.clone().absolute_path()));
+}
+
+/// Checks that get_first() prefers earlier names over later roots and respects overrides.
+#[test]
+fn test_dirset_get_first() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let file = set.get_first(&["missing.txt", "beta.txt", "epsilon.txt"]).unwrap();
+    assert_eq!(file.path(), std::path::Path::new("beta.txt"));
+    let file = set.get_first(&["alpha.txt", "beta.txt"]).unwrap();
+    assert!(file.absolute_path().to_string_lossy().contains("override"));
+    assert!(set.get_first(&["missing.txt"]).is_none());
+    assert!(set.get_first(&[]).is_none());
+}
+
+/// Checks that get_file_spa() falls back to the index except under asset prefixes.
+#[test]
+fn test_dirset_get_file_spa() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let spa = |path| set.get_file_spa(path, "beta.txt", &["subdir", "/static/"]);
+    assert_eq!(
+        spa("/alpha.txt").unwrap(),
+        set.get_file("alpha.txt").unwrap()
+    );
+    assert_eq!(
+        spa("/subdir/gamma.txt").unwrap().rel_url(),
+        "subdir/gamma.txt"
+    );
+    assert_eq!(spa("/settings/profile").unwrap().rel_url(), "beta.txt");
+    assert_eq!(spa("/").unwrap().rel_url(), "beta.txt");
+    assert_eq!(spa("/subdirectory/x").unwrap().rel_url(), "beta.txt");
+    assert!(spa("/subdir/missing.js").is_none());
+    assert!(spa("/static/app.js").is_none());
+    assert!(spa("/../../Cargo.toml").is_none());
+    assert!(set.get_file_spa("/route", "missing.html", &[]).is_none());
+}
+
+/// Checks that walk_dirs() yields every subdirectory and no files.
+#[test]
+fn test_walk_dirs() {
+    let mut paths: Vec<_> = test_dir().walk_dirs().map(|d| d.path().to_owned()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![std::path::PathBuf::from("override"), std::path::PathBuf::from("subdir"), std::path::PathBuf::from("subdir/subsubdir")]
+    );
+}
+
+/// Checks that size_report() sums subdirectories and sorts children by size, largest first.
+#[test]
+fn test_dir_size_report() {
+    let dir = test_dir();
+    let report = dir.size_report();
+    assert_eq!(report.len(), dir.entries().len());
+    assert!(report.windows(2).all(|w| w[0].1 >= w[1].1));
+    let subdir_total: u64 = dir.get_dir("subdir").unwrap().walk().map(|f| f.metadata().unwrap().size).sum();
+    assert!(report.contains(&(std::path::PathBuf::from("subdir"), subdir_total)));
+    let alpha = dir.get_file("alpha.txt").unwrap().metadata().unwrap().size;
+    assert!(report.contains(&(std::path::PathBuf::from("alpha.txt"), alpha)));
+}
+
+static MIME_OVERRIDES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "txt" => "text/plain; charset=utf-8",
+    "tmpl" => "text/x-template",
+};
+
+/// Checks that content_type() uses the default table and content_type_with() prefers overrides.
+#[test]
+fn test_file_content_type_with() {
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    assert_eq!(file.content_type(), Some("text/plain"));
+    assert_eq!(file.content_type_with(&MIME_OVERRIDES), Some("text/plain; charset=utf-8"));
+    let custom = test_dir().with_file("page.TMPL", Vec::new()).get_file("page.TMPL").unwrap();
+    assert_eq!(custom.content_type(), None);
+    assert_eq!(custom.content_type_with(&MIME_OVERRIDES), Some("text/x-template"));
+}
+
+/// Checks at compile time that File, Dir and DirEntry are 'static, and that into_owned() keeps the file.
+#[test]
+fn test_file_into_owned_is_static() {
+    fn assert_static<T: 'static>(_: &T) {}
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    let owned = file.clone().into_owned();
+    assert_static(&owned);
+    assert_static(&test_dir());
+    assert_static(&test_dir().entries());
+    assert_eq!(owned, file);
+    assert_eq!(owned.read_bytes().unwrap(), file.read_bytes().unwrap());
+}
+
+/// Checks that filter() hides non-matching files and prunes directories left empty.
+#[test]
+fn test_dir_filter() {
+    let dir = test_dir();
+    let only_zeta = dir.filter(|file| file.file_name() == Some("zeta.txt"));
+    let paths: Vec<String> = only_zeta.walk().map(|f| f.rel_url()).collect();
+    assert_eq!(paths, vec!["subdir/subsubdir/zeta.txt".to_string()]);
+    assert!(only_zeta.get_file("alpha.txt").is_none());
+    assert!(only_zeta.get_file("subdir/subsubdir/zeta.txt").is_some());
+    assert!(only_zeta.get_dir("override").is_none());
+    let entries = only_zeta.entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path(), std::path::Path::new("subdir"));
+
+    let set = DirSet::new(vec![only_zeta, test_override_dir()]);
+    assert!(set.get_file("subdir/subsubdir/zeta.txt").is_some());
+    assert!(set.get_file("subdir/gamma.txt").is_none());
+}
+
+/// Checks that walking a filtered tree checks each file a bounded number of times, not once
+/// per ancestor directory.
+#[test]
+fn test_dir_filter_walk_is_linear() {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    let temp_dir = test_temp_dir("filter_deep");
+    let mut path = temp_dir.path().to_path_buf();
+    for depth in 0..20 {
+        path.push(format!("d{depth}"));
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("f.txt"), b"x").unwrap();
+    }
+    std::fs::write(path.join("deepest.txt"), b"x").unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let dir = Dir::from_path(temp_dir.path()).filter(move |file| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        file.file_name() == Some("deepest.txt")
+    });
+    assert_eq!(dir.walk().count(), 1);
+    assert!(calls.load(Ordering::Relaxed) <= 3 * 21, "{calls:?}");
+}
+
+/// Checks that flatten() resolves overrides into a single Dir that walks like walk_override().
+#[test]
+fn test_dirset_flatten() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let flat = set.flatten();
+    let alpha = flat.get_file("alpha.txt").unwrap();
+    assert_eq!(alpha.absolute_path(), set.get_file("alpha.txt").unwrap().absolute_path());
+    assert!(flat.get_file("epsilon.txt").is_some());
+    assert!(flat.get_file("subdir/subsubdir/zeta.txt").is_some());
+
+    let mut flat_paths = flat.paths();
+    let mut override_paths: Vec<String> = set.walk_override().map(|f| f.rel_url()).collect();
+    flat_paths.sort();
+    override_paths.sort();
+    assert_eq!(flat_paths, override_paths);
+    assert_eq!(DirSet::new(vec![]).flatten().walk().count(), 0);
+}
+
+/// Checks that read_prefix() returns at most n bytes from the start of the file.
+#[test]
+fn test_file_read_prefix() {
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    let contents = file.read_bytes().unwrap();
+    assert_eq!(file.read_prefix(4).unwrap(), contents[..4]);
+    assert_eq!(file.read_prefix(usize::MAX).unwrap(), contents);
+    assert!(file.read_prefix(0).unwrap().is_empty());
+}
+
+/// Checks that sniff_content_type() recognizes a file by its magic bytes, not its extension.
+#[cfg(feature = "infer")]
+#[test]
+fn test_file_sniff_content_type() {
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+    let dir = test_dir().with_file("image.bin", png);
+    assert_eq!(dir.get_file("image.bin").unwrap().sniff_content_type(), Some("image/png"));
+    assert_eq!(dir.get_file("alpha.txt").unwrap().sniff_content_type(), None);
+}
+
+/// Checks that the walk iterators are Send and can be consumed on another thread.
+#[test]
+fn test_walk_iterators_are_send() {
+    fn assert_send<T: Send>(_: &T) {}
+    let dir = test_dir();
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    assert_send(&dir.walk());
+    assert_send(&dir.walk_sorted());
+    assert_send(&dir.walk_entries_keep_empty());
+    assert_send(&set.walk());
+    assert_send(&set.walk_override());
+
+    let expected = dir.walk().count();
+    let count = std::thread::scope(|scope| {
+        let walk = dir.walk();
+        scope.spawn(move || walk.count()).join().unwrap()
+    });
+    assert_eq!(count, expected);
+}
+
+/// Checks that get_dir() ignores a leading and a trailing slash, for Dir and DirSet alike.
+#[test]
+fn test_get_dir_slashes() {
+    let dir = test_dir();
+    for name in ["subdir", "subdir/", "/subdir/", "/subdir", "subdir/subsubdir/"] {
+        assert!(dir.get_dir(name).is_some(), "{name}");
+    }
+    assert_eq!(dir.get_dir("/subdir/").unwrap().path(), std::path::Path::new("subdir"));
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    assert!(set.get_dir("/subdir/").is_some());
+}
+
+/// Checks that siblings() lists the other files in the same directory, sorted by name.
+#[test]
+fn test_file_siblings() {
+    let gamma = test_dir().get_file("subdir/gamma.txt").unwrap();
+    let names: Vec<_> = gamma.siblings().unwrap().iter().map(|f| f.rel_url()).collect();
+    assert_eq!(names, vec!["subdir/delta.txt".to_string()]);
+    let memory = test_dir().with_file("new.txt", Vec::new()).get_file("new.txt").unwrap();
+    assert_eq!(memory.siblings().unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+}
+
+/// Checks that validate() reports unmatched overrides and case-insensitive duplicates.
+#[test]
+fn test_dirset_validate() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let issues = set.validate();
+    assert_eq!(
+        issues,
+        vec![fs_embed::ValidationIssue::UnmatchedOverride { layer: 1, path: std::path::PathBuf::from("epsilon.txt") }]
+    );
+    assert!(issues[0].to_string().contains("epsilon.txt"));
+
+    let temp_dir = test_temp_dir("validate");
+    std::fs::write(temp_dir.path().join("Alpha.txt"), b"a").unwrap();
+    std::fs::write(temp_dir.path().join("alpha.txt"), b"b").unwrap();
+    let set = DirSet::new(vec![Dir::from_path(temp_dir.path())]);
+    assert_eq!(
+        set.validate(),
+        vec![fs_embed::ValidationIssue::DuplicatePath { layer: 0, path: std::path::PathBuf::from("alpha.txt") }]
+    );
+}
+
+/// Checks that grouped_by_dir() buckets files by parent in sorted order.
+#[test]
+fn test_grouped_by_dir() {
+    let groups: Vec<(std::path::PathBuf, Vec<String>)> = test_dir()
+        .grouped_by_dir()
+        .map(|(dir, files)| (dir, files.iter().map(|f| f.file_name().unwrap().to_string()).collect()))
+        .collect();
+    let dirs: Vec<_> = groups.iter().map(|(dir, _)| dir.clone()).collect();
+    assert_eq!(
+        dirs,
+        ["", "override", "subdir", "subdir/subsubdir"].iter().map(std::path::PathBuf::from).collect::<Vec<_>>()
+    );
+    assert_eq!(groups[2].1, vec!["delta.txt".to_string(), "gamma.txt".to_string()]);
+}
+
+/// Checks that rel_url_encoded() escapes each segment and round-trips through get_file_url().
+#[test]
+fn test_rel_url_encoded() {
+    let temp_dir = test_temp_dir("url_encoded");
+    std::fs::create_dir(temp_dir.path().join("my docs")).unwrap();
+    std::fs::write(temp_dir.path().join("my docs/a#b?c é.txt"), b"x").unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    let file = dir.get_file("my docs/a#b?c é.txt").unwrap();
+    let url = file.rel_url_encoded();
+    assert_eq!(url, "my%20docs/a%23b%3Fc%20%C3%A9.txt");
+    assert_eq!(dir.get_file_url(&url).unwrap().path(), file.path());
+    assert_eq!(
+        test_dir()
+            .get_file("subdir/gamma.txt")
+            .unwrap()
+            .rel_url_encoded(),
+        "subdir/gamma.txt"
+    );
+}
+
+/// Checks that resolve_relative() follows `.`/`..` from the file's directory and rejects escapes.
+#[test]
+fn test_resolve_relative() {
+    let dir = test_dir();
+    let zeta = dir.get_file("subdir/subsubdir/zeta.txt").unwrap();
+    let gamma = zeta.resolve_relative(&dir, "../gamma.txt").unwrap();
+    assert_eq!(
+        gamma.path(),
+        dir.get_file("subdir/gamma.txt").unwrap().path()
+    );
+    assert!(zeta.resolve_relative(&dir, "./../../alpha.txt").is_some());
+    assert!(zeta.resolve_relative(&dir, "/alpha.txt").is_some());
+    assert!(zeta.resolve_relative(&dir, "../../../alpha.txt").is_none());
+    assert!(zeta.resolve_relative(&dir, "missing.txt").is_none());
+
+    let subdir = dir.get_dir("subdir").unwrap();
+    let zeta = subdir.get_file("subsubdir/zeta.txt").unwrap();
+    assert!(zeta.resolve_relative(&subdir, "../gamma.txt").is_some());
+    assert!(zeta.resolve_relative(&subdir, "../../alpha.txt").is_none());
+    assert!(zeta.resolve_relative(&subdir, "/gamma.txt").is_some());
+}
+
+/// Checks that any() and all() short-circuit on the first decisive file.
+#[test]
+fn test_dir_any_all() {
+    let dir = test_dir();
+    assert!(dir.any(|f| f.file_name() == Some("zeta.txt")));
+    assert!(!dir.any(|f| f.extension() == Some("png")));
+    assert!(dir.all(|f| f.extension() == Some("txt")));
+    assert!(!dir.all(|f| f.file_name() == Some("alpha.txt")));
+
+    let visited = std::cell::Cell::new(0);
+    assert!(dir.any(|_| {
+        visited.set(visited.get() + 1);
+        true
+    }));
+    assert_eq!(visited.get(), 1);
+    visited.set(0);
+    assert!(!dir.all(|_| {
+        visited.set(visited.get() + 1);
+        false
+    }));
+    assert_eq!(visited.get(), 1);
+}
+
+/// Checks that from_path_checked() rejects missing paths and files but accepts directories.
+#[test]
+fn test_from_path_checked() {
+    let base = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let dir = Dir::from_path_checked(&base).unwrap();
+    assert!(dir.get_file("alpha.txt").is_some());
+
+    let err = Dir::from_path_checked(&base.join("missing")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    let err = Dir::from_path_checked(&base.join("alpha.txt")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotADirectory);
+}
+
+/// Checks that read_many() keeps input order, honours overrides and reports missing names in place.
+#[test]
+fn test_dirset_read_many() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let results = set.read_many(&["alpha.txt", "missing.txt", "subdir/gamma.txt"]);
+    assert_eq!(results.len(), 3);
+    let (name, bytes) = results[0].as_ref().unwrap();
+    assert_eq!(name, "alpha.txt");
+    assert_eq!(
+        bytes,
+        &set.get_file("alpha.txt").unwrap().read_bytes().unwrap()
+    );
+    assert_eq!(
+        results[1].as_ref().unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert_eq!(results[2].as_ref().unwrap().0, "subdir/gamma.txt");
+}
+
+/// Checks that a single Dir converts into an equivalent one-layer DirSet.
+#[test]
+fn test_dir_into_dir_set() {
+    fn count(set: impl Into<DirSet>) -> usize {
+        set.into().walk_override().count()
+    }
+    assert_eq!(count(test_dir()), test_dir().walk().count());
+    let set = test_dir().into_dir_set();
+    assert_eq!(set.flatten().paths(), test_dir().paths());
+    assert!(set.get_file("subdir/gamma.txt").is_some());
+}
+
+/// Checks that extract_override_to() writes the winning version of every file.
+#[test]
+fn test_dirset_extract_override_to() {
+    let temp_dir = test_temp_dir("extract_override");
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let written = set.extract_override_to(temp_dir.path()).unwrap();
+    assert_eq!(written.len(), set.walk_override().count());
+    for file in set.walk_override() {
+        let target = temp_dir.path().join(file.path());
+        assert!(written.contains(&target));
+        assert_eq!(std::fs::read(&target).unwrap(), file.read_bytes().unwrap());
+    }
+}
+
+/// Checks that diff() reports added, removed and changed paths between two resolved sets.
+#[test]
+fn test_diff_dir_sets() {
+    let old = DirSet::new(vec![test_dir()]);
+    assert!(diff(&old, &old).unwrap().is_empty());
+
+    let new = DirSet::new(vec![
+        test_dir()
+            .with_file("alpha.txt", b"changed".to_vec())
+            .with_file("added.txt", b"new".to_vec())
+            .filter(|f| f.file_name() != Some("beta.txt")),
+    ]);
+    let result = diff(&old, &new).unwrap();
+    assert_eq!(result.added, vec![std::path::PathBuf::from("added.txt")]);
+    assert_eq!(result.removed, vec![std::path::PathBuf::from("beta.txt")]);
+    assert_eq!(result.changed, vec![std::path::PathBuf::from("alpha.txt")]);
+}
+
+/// Checks that read_str_with() applies the transform for both File and DirSet lookups.
+#[test]
+fn test_read_str_with() {
+    let file = test_dir().get_file("alpha.txt").unwrap();
+    let upper = file.read_str_with(|s| s.to_uppercase()).unwrap();
+    assert_eq!(upper, file.read_str().unwrap().to_uppercase());
+
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let len = set
+        .read_str_with("alpha.txt", |s| s.len().to_string())
+        .unwrap();
+    assert_eq!(
+        len,
+        set.get_file("alpha.txt")
+            .unwrap()
+            .read_str()
+            .unwrap()
+            .len()
+            .to_string()
+    );
+    let err = set.read_str_with("missing.txt", str::to_owned).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+/// Checks that walk_max_depth() stops descending and try_walk() reports the skipped directories.
+#[test]
+fn test_walk_max_depth() {
+    let dir = test_dir();
+    let top: Vec<_> = dir.walk_max_depth(1).map(|f| f.rel_url()).collect();
+    assert!(top.iter().all(|p| !p.contains('/')));
+    assert!(!top.is_empty());
+    assert_eq!(dir.walk_max_depth(0).count(), 0);
+    let two = dir.walk_max_depth(2).count();
+    assert!(two > top.len() && two < dir.walk().count());
+    assert_eq!(
+        dir.walk_max_depth(DEFAULT_WALK_DEPTH).count(),
+        dir.walk().count()
+    );
+
+    let truncated: Vec<_> = dir.try_walk(2).filter_map(Result::err).collect();
+    assert_eq!(truncated.len(), 1);
+    assert!(truncated[0].to_string().contains("subsubdir"));
+    assert!(dir.try_walk(DEFAULT_WALK_DEPTH).all(|r| r.is_ok()));
+}
+
+/// Checks that every recursive walk terminates on a symlink cycle in dynamic mode.
+#[cfg(unix)]
+#[test]
+fn test_walk_symlink_cycle_terminates() {
+    let temp_dir = test_temp_dir("walk_cycle");
+    std::fs::create_dir(temp_dir.path().join("a")).unwrap();
+    std::fs::write(temp_dir.path().join("a/file.txt"), b"x").unwrap();
+    std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("a/loop")).unwrap();
+    let dir = Dir::from_path(temp_dir.path());
+    assert_eq!(dir.walk_max_depth(4).count(), 2);
+    assert!(dir.try_walk(4).any(|r| r.is_err()));
+
+    let files = dir.walk().count();
+    assert_eq!(files, DEFAULT_WALK_DEPTH / 2);
+    assert_eq!(dir.walk_sorted().count(), files);
+    assert_eq!(dir.walk_under("a").count(), files);
+    assert_eq!(dir.walk_pruned(|_| true).count(), files);
+    assert_eq!(dir.walk_dirs().count(), DEFAULT_WALK_DEPTH);
+    assert_eq!(dir.walk_entries_keep_empty().count(), files + DEFAULT_WALK_DEPTH);
+    assert_eq!(dir.count_entries(), (DEFAULT_WALK_DEPTH, files));
+    let set = dir.into_dir_set();
+    assert_eq!(set.walk().count(), files);
+    assert_eq!(set.walk_override().count(), files);
+}
+
+/// Checks that compact() keeps overlay contents intact and leaves other backends untouched.
+#[test]
+fn test_dir_compact() {
+    let mut contents = Vec::with_capacity(4096);
+    contents.extend_from_slice(b"small");
+    let mut dir = test_dir()
+        .with_file("alpha.txt", contents)
+        .with_file("new.txt", b"new".to_vec());
+    dir.compact();
+    assert_eq!(
+        dir.get_file("alpha.txt").unwrap().read_bytes().unwrap(),
+        b"small"
+    );
+    let shared = dir.clone();
+    dir.compact();
+    assert_eq!(
+        dir.get_file("alpha.txt").unwrap().read_bytes().unwrap(),
+        b"small"
+    );
+    assert_eq!(
+        shared.get_file("alpha.txt").unwrap().read_bytes().unwrap(),
+        b"small"
+    );
+    assert_eq!(dir.paths(), shared.paths());
+
+    let mut plain = test_dir();
+    plain.compact();
+    assert_eq!(plain.paths(), test_dir().paths());
+}
+
+/// Checks that expand() lists one level of a directory entry and returns None for files.
+#[test]
+fn test_dir_entry_expand() {
+    let entries = test_dir().entries();
+    let subdir = entries.iter().find(|e| e.name() == Some("subdir")).unwrap();
+    let mut names: Vec<_> = subdir
+        .expand()
+        .unwrap()
+        .iter()
+        .map(|e| e.name().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["delta.txt", "gamma.txt", "subsubdir"]);
+    let file = entries.iter().find(|e| e.is_file()).unwrap();
+    assert!(file.expand().is_none());
+}
+
+/// Checks that link_to() walks up to the common ancestor and back down to the target.
+#[test]
+fn test_file_link_to() {
+    let dir = test_dir();
+    let zeta = dir.get_file("subdir/subsubdir/zeta.txt").unwrap();
+    let gamma = dir.get_file("subdir/gamma.txt").unwrap();
+    let alpha = dir.get_file("alpha.txt").unwrap();
+    assert_eq!(
+        zeta.link_to(&gamma),
+        std::path::PathBuf::from("../gamma.txt")
+    );
+    assert_eq!(
+        zeta.link_to(&alpha),
+        std::path::PathBuf::from("../../alpha.txt")
+    );
+    assert_eq!(
+        alpha.link_to(&zeta),
+        std::path::PathBuf::from("subdir/subsubdir/zeta.txt")
+    );
+    assert_eq!(gamma.link_to(&gamma), std::path::PathBuf::from("gamma.txt"));
+
+    let overlay = dir
+        .with_file("a/b/page.html", Vec::new())
+        .with_file("a/c/other.html", Vec::new());
+    let page = overlay.get_file("a/b/page.html").unwrap();
+    let other = overlay.get_file("a/c/other.html").unwrap();
+    assert_eq!(
+        page.link_to(&other),
+        std::path::PathBuf::from("../c/other.html")
+    );
+}
+
+/// Checks that is_binary() flags NUL bytes and control-heavy contents but not text.
+#[test]
+fn test_file_is_binary() {
+    let dir = test_dir()
+        .with_file("nul.bin", b"PK\x03\x04\0\0data".to_vec())
+        .with_file("control.bin", vec![0x01, 0x02, 0x03, b'a', 0x7f, 0x05])
+        .with_file("utf8.txt", "héllo wörld\r\n\tindent".as_bytes().to_vec())
+        .with_file("empty.txt", Vec::new());
+    assert!(!dir.get_file("alpha.txt").unwrap().is_binary().unwrap());
+    assert!(dir.get_file("nul.bin").unwrap().is_binary().unwrap());
+    assert!(dir.get_file("control.bin").unwrap().is_binary().unwrap());
+    assert!(!dir.get_file("utf8.txt").unwrap().is_binary().unwrap());
+    assert!(!dir.get_file("empty.txt").unwrap().is_binary().unwrap());
+}
+
+/// Checks that walk_override_typed() yields the override walk with each file's content type.
+#[test]
+fn test_dirset_walk_override_typed() {
+    let set = DirSet::new(vec![
+        test_dir().with_file("logo.svg", b"<svg/>".to_vec()),
+        test_override_dir(),
+    ]);
+    let typed: Vec<_> = set.walk_override_typed().collect();
+    assert_eq!(typed.len(), set.walk_override().count());
+    for (file, content_type) in &typed {
+        assert_eq!(*content_type, file.content_type());
+    }
+    let svg = typed
+        .iter()
+        .find(|(f, _)| f.rel_url() == "logo.svg")
+        .unwrap();
+    assert_eq!(svg.1, Some("image/svg+xml"));
+}
+
+/// Checks that from_exe_relative() resolves against the test binary's directory.
+#[test]
+fn test_from_exe_relative() {
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .canonicalize()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let dir = Dir::from_exe_relative("assets").unwrap();
+    assert_eq!(dir.absolute_path(), exe_dir.join("assets"));
+    assert_eq!(dir.origin(), fs_embed::Origin::Filesystem);
+    assert!(!dir.is_embedded());
+}
+
+/// Checks that preload() snapshots every resolved file with its content type and ETag.
+#[test]
+fn test_dirset_preload() {
+    let set = DirSet::new(vec![test_dir(), test_override_dir()]);
+    let preloaded = set.preload().unwrap();
+    assert_eq!(preloaded.len(), set.walk_override().count());
+    for file in set.walk_override() {
+        let entry = preloaded.get_file(&file.rel_url()).unwrap();
+        assert_eq!(&entry.contents()[..], &file.read_bytes().unwrap()[..]);
+        assert_eq!(entry.content_type(), file.content_type());
+        assert_eq!(entry.etag(), file.etag().unwrap());
+    }
+    assert!(preloaded.get("/subdir/gamma.txt").is_some());
+    assert!(preloaded.get("missing.txt").is_none());
+    assert!(set.get_file("subdir/../gamma.txt").is_none());
+    assert!(preloaded.get("subdir/../gamma.txt").is_none());
+    assert_eq!(preloaded.get("subdir/../alpha.txt"), preloaded.get("alpha.txt"));
+    assert!(preloaded.get("../alpha.txt").is_none());
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    assert_send_sync(&preloaded);
+}
+
+/// Checks that get_file_by() picks by the comparator and falls back to position on ties.
+#[test]
+fn test_dirset_get_file_by() {
+    let small = test_dir().with_file("pick.txt", b"a".to_vec());
+    let large = test_dir().with_file("pick.txt", b"abc".to_vec());
+    let tie = test_dir().with_file("pick.txt", b"xyz".to_vec());
+    let set = DirSet::new(vec![large, small, tie]);
+    let by_size = |a: &File, b: &File| a.metadata().unwrap().size.cmp(&b.metadata().unwrap().size);
+
+    let picked = set.get_file_by("pick.txt", by_size).unwrap();
+    assert_eq!(picked.read_bytes().unwrap(), b"xyz");
+    let smallest = set.get_file_by("pick.txt", |a, b| by_size(b, a)).unwrap();
+    assert_eq!(smallest.read_bytes().unwrap(), b"a");
+    assert!(set.get_file_by("missing.txt", by_size).is_none());
+    assert_eq!(
+        set.get_file("pick.txt").unwrap().read_bytes().unwrap(),
+        b"xyz"
+    );
+}
+
+/// Checks that walk_dedup_content() yields each distinct content once.
+#[test]
+fn test_dirset_walk_dedup_content() {
+    let base = test_dir()
+        .with_file("copy_one.bin", b"same bytes".to_vec())
+        .with_file("copy_two.bin", b"same bytes".to_vec());
+    let set = DirSet::new(vec![base]);
+    let deduped: Vec<_> = set.walk_dedup_content().collect();
+    assert_eq!(deduped.len(), set.walk_override().count() - 1);
+    let copies = deduped
+        .iter()
+        .filter(|f| f.extension() == Some("bin"))
+        .count();
+    assert_eq!(copies, 1);
+    let mut digests: Vec<_> = deduped.iter().map(|f| f.etag().unwrap()).collect();
+    digests.sort();
+    digests.dedup();
+    assert_eq!(digests.len(), deduped.len());
+}
+
+/// Checks that canonical_path() resolves symlinks and dedup_roots() collapses linked roots.
+#[cfg(unix)]
+#[test]
+fn test_canonical_path_and_symlinked_roots() {
+    let temp_dir = test_temp_dir("canonical");
+    let real = temp_dir.path().join("real");
+    std::fs::create_dir(&real).unwrap();
+    std::fs::write(real.join("file.txt"), b"x").unwrap();
+    let link = temp_dir.path().join("link");
+    std::os::unix::fs::symlink(&real, &link).unwrap();
+
+    let via_real = Dir::from_path(&real);
+    let via_link = Dir::from_path(&link);
+    assert!(!via_real.same_root(&via_link));
+    assert_eq!(
+        via_link.canonical_path().unwrap(),
+        real.canonicalize().unwrap()
+    );
+    assert!(
+        Dir::from_path(&temp_dir.path().join("missing"))
+            .canonical_path()
+            .is_err()
+    );
+
+    let mut set = DirSet::new(vec![via_real, test_dir(), via_link]);
+    set.dedup_roots();
+    assert_eq!(set.dirs.len(), 2);
+    assert!(set.dirs[1].absolute_path().ends_with("link"));
+    let subdir = test_dir().get_dir("subdir").unwrap();
+    assert_eq!(
+        subdir.canonical_path().unwrap(),
+        subdir.absolute_path().canonicalize().unwrap()
+    );
+}
@@ -19,3 +19,119 @@ fn test_fs_embed_missing_file() {
     let dir = fs_embed!("tests/data");
     assert!(dir.get_file("notfound.txt").is_none());
 }
+
+/// Checks that fs_embed! with `mount` prefixes embedded relative paths.
+#[test]
+fn test_fs_embed_mount() {
+    let dir = fs_embed!("tests/data/subdir", mount = "assets/docs");
+    let file = dir.get_file("assets/docs/gamma.txt").expect("mounted file missing");
+    assert_eq!(file.path(), std::path::Path::new("assets/docs/gamma.txt"));
+    assert!(dir.get_file("gamma.txt").is_none());
+    let mounted = dir.get_dir("assets/docs").expect("mount dir missing");
+    assert!(mounted.get_file("subsubdir/zeta.txt").is_some());
+    assert_eq!(dir.walk().count(), 3);
+}
+
+static ALPHA: &[u8] = fs_embed_file!("tests/data/alpha.txt");
+
+/// Checks that fs_embed_file! embeds a single file usable in a static.
+#[test]
+fn test_fs_embed_file() {
+    let content = std::str::from_utf8(ALPHA).unwrap();
+    assert!(content.contains("Hello from alpha!"));
+}
+
+/// Checks that fs_embed! accepts the `allow_non_utf8` opt-out alongside other options.
+#[test]
+fn test_fs_embed_allow_non_utf8() {
+    let dir = fs_embed!("tests/data", allow_non_utf8 = true, mount = "x");
+    assert!(dir.get_file("x/alpha.txt").is_some());
+}
+
+/// Checks that `normalize_newlines` strips CRLF from text files but leaves binary files alone.
+#[test]
+fn test_fs_embed_normalize_newlines() {
+    let dir = fs_embed!("tests/crlf", normalize_newlines = true);
+    let text = dir.get_file("lines.txt").unwrap();
+    assert_eq!(text.read_bytes().unwrap(), b"one\ntwo\n");
+    let binary = dir.get_file("blob.bin").unwrap();
+    assert_eq!(binary.read_bytes().unwrap(), b"bin\0\r\n");
+
+    let raw = fs_embed!("tests/crlf");
+    let text = raw.get_file("lines.txt").unwrap();
+    assert_eq!(text.read_bytes().unwrap(), b"one\r\ntwo\r\n");
+}
+
+/// Checks that fs_embed! emits every directory's entries in sorted path order.
+#[test]
+fn test_fs_embed_sorted_entries() {
+    fn assert_sorted(dir: &Dir) {
+        let entries = dir.entries();
+        for kind in [DirEntry::is_file, DirEntry::is_dir] {
+            let paths: Vec<_> = entries
+                .iter()
+                .filter(|e| kind(e))
+                .map(|e| e.path())
+                .collect();
+            let mut sorted = paths.clone();
+            sorted.sort();
+            assert_eq!(paths, sorted, "unsorted entries in {:?}", dir.path());
+        }
+        for subdir in entries.into_iter().filter_map(DirEntry::into_dir) {
+            assert_sorted(&subdir);
+        }
+    }
+    let dir = fs_embed!("tests/data");
+    assert!(dir.is_embedded());
+    assert_sorted(&dir);
+    let order: Vec<_> = dir.walk().map(|f| f.rel_url()).collect();
+    let again: Vec<_> = fs_embed!("tests/data")
+        .walk()
+        .map(|f| f.rel_url())
+        .collect();
+    assert_eq!(order, again);
+}
+
+/// Checks that `metadata = false` embeds no timestamps, that mtime consumers fall back to the
+/// epoch, and that the default keeps the mtime.
+#[test]
+fn test_fs_embed_without_metadata() {
+    let bare = fs_embed!("tests/data", metadata = false);
+    assert!(bare.walk().all(|file| file.metadata().is_err()));
+    let alpha = bare.get_file("alpha.txt").unwrap();
+    let size = alpha.read_bytes().unwrap().len();
+    assert_eq!(alpha.weak_etag().unwrap(), format!("W/\"{size:x}-0\""));
+    let latest = bare.clone().into_dir_set().latest_modified().unwrap();
+    assert_eq!(latest, std::time::UNIX_EPOCH);
+    let dir = fs_embed!("tests/data");
+    let file = dir.get_file("alpha.txt").unwrap();
+    let on_disk = std::fs::metadata(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/alpha.txt"));
+    let modified =
+        |t: std::time::SystemTime| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    assert_eq!(
+        modified(file.metadata().unwrap().modified),
+        modified(on_disk.unwrap().modified().unwrap())
+    );
+}
+
+/// Checks that fs_embed! accepts the `allow_empty` opt-out alongside other options.
+#[test]
+fn test_fs_embed_allow_empty() {
+    let dir = fs_embed!("tests/data/subdir", allow_empty = true, mount = "x");
+    assert!(dir.get_file("x/gamma.txt").is_some());
+}
+
+/// Checks that `build_id = true` records an id that subdirectories inherit and dynamic mode drops.
+#[test]
+fn test_fs_embed_build_id() {
+    static VERSIONED: Dir = fs_embed!("tests/data", build_id = true);
+    let dir = VERSIONED.clone();
+    let id = dir.build_id().expect("build id recorded");
+    match option_env!("FS_EMBED_BUILD_ID") {
+        Some(expected) => assert_eq!(id, expected),
+        None => assert!(id.parse::<u64>().unwrap() > 0),
+    }
+    assert_eq!(dir.get_dir("subdir").unwrap().build_id(), Some(id));
+    assert!(dir.clone().into_dynamic().build_id().is_none());
+    assert!(fs_embed!("tests/data").build_id().is_none());
+}